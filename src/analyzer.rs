@@ -1,12 +1,23 @@
-use crate::analyzer_data::AnalyzerData;
-use crate::{article, DEFAULT_HALLMARKS};
+use crate::analyzer_data::{cooccurrence_increment, AnalyzerData, FullRunOutput};
+use crate::article::{Article, InputSource};
+use crate::config::{format_input_filename, AnalyzerConfig, NonFiniteHandling, TokenizerConfig};
+use crate::ensemble::Ensemble;
+use crate::error::{AnalyzerError, InputError};
+use crate::output::{check_format_version, is_jsonl, read_output_file, OutputFile, FORMAT_VERSION};
+use crate::summary::{report, CorpusDistribution, DroppedPublications, RunSummary};
+use crate::tokenizer::{tokens_of, variant_key, Tokenizer};
+use crate::{article, default_hallmark_titles, DEFAULT_HALLMARKS};
+use rayon::prelude::*;
 use serde::ser::{SerializeSeq, Serializer};
-use regex::Regex;
-use serde::Serialize;
-use std::fs;
-use std::{collections::HashMap, io::Write};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
 
-fn serialize_f32_vec<S>(vec: &Vec<f32>, serializer: S) -> Result<S::Ok, S::Error>
+fn serialize_f32_vec<S>(vec: &[f32], serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -17,33 +28,327 @@ where
     seq.end()
 }
 
+fn serialize_f32_option<S>(value: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(num) => serializer.serialize_str(&format!("{:.3}", num)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_f32_option<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|num| num.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_f32_vec<'de, D>(deserializer: D) -> Result<Vec<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|num| num.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
 pub struct Analyzer {
     filenames: Vec<String>,
     keyword_candidates: HashMap<String, usize>,
-    lower_cutoff: f32,
-    upper_cutoff: f32,
+    n_scanned_articles: usize,
+    total_articles: Option<usize>,
+    rating_total_articles: Option<usize>,
+    /// Number of articles of every source read so far, by source name, recorded for
+    /// `article_progress`.
+    article_counts: HashMap<String, usize>,
+    /// Token counts of every abstract of every training source from the candidate scan,
+    /// kept with `cache_tokens` until the relations matrix is built.
+    token_cache: Vec<Vec<Vec<(String, usize)>>>,
+    /// Co-occurrences of the keyword candidates from the candidate scan, kept with
+    /// `single_pass` until the relations matrix is built.
+    cooccurrence_record: CooccurrenceRecord,
+    config: AnalyzerConfig,
+    tokenizer: Tokenizer,
+    summary: RunSummary,
     bar_style: indicatif::ProgressStyle,
+    progress_callback: Option<ProgressCallback>,
+    article_id: Option<ArticleId>,
+    cancellation_flag: Option<Arc<AtomicBool>>,
+    /// Source names and indices of the articles skipped by `read_source`.
+    malformed_articles: Mutex<HashSet<(String, usize)>>,
+    short_articles: Mutex<HashSet<(String, usize)>>,
+    /// The abstract count chosen by `tune_lower_cutoff`. The purge keeps candidates found in
+    /// more abstracts than this, since `lower_cutoff * n_files` need not give it back
+    /// exactly.
+    tuned_lower_bound: Option<usize>,
 }
 
+/// The co-occurrences of all keyword candidates, recorded by the candidate scan in
+/// `single_pass` mode. Candidates are referred to by ids in order of first occurrence.
+#[derive(Default)]
+struct CooccurrenceRecord {
+    candidate_ids: HashMap<String, u32>,
+    /// Summed relations matrix increments by pair of candidate ids, lower id first.
+    pairs: HashMap<(u32, u32), f32>,
+    /// The candidate ids of every abstract of every source, for the histogram of relevant
+    /// words and the usable-article counts of the matrix build.
+    articles: Vec<Vec<Vec<u32>>>,
+}
+
+/// The keyword candidates of the candidate scan before any cutoff, as written to
+/// `candidate_output_path`.
 #[derive(Serialize, Debug)]
+pub struct CandidateFrequencyOutput {
+    pub n_files: usize,
+    pub n_articles: usize,
+    /// Keywords with the number of abstracts containing them, most frequent first.
+    pub candidates: Vec<(String, usize)>,
+    /// Pairs of an abstract count and the number of candidates with exactly that count.
+    pub histogram: Vec<(usize, usize)>,
+}
+
+/// Anything that turns the tokens of an abstract into a hallmark rating, e.g. a trained
+/// `AnalyzerData` or an `Ensemble` of loaded rating databases.
+pub trait PublicationRater: Sync {
+    fn rate_article_keywords(&self, words: Vec<String>, id: String) -> RatedPublication;
+
+    /// Whether the tokens contain enough keywords to be rated at all. Publications failing
+    /// this are dropped before rating and counted apart from invalid ratings.
+    fn has_enough_keywords(&self, _words: &[String]) -> bool {
+        true
+    }
+
+    /// The titles of the hallmarks the ratings are indexed by.
+    fn hallmark_titles(&self) -> Vec<String> {
+        default_hallmark_titles()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RatedPublication {
     pub i: String,
-    #[serde(serialize_with = "serialize_f32_vec")]
+    #[serde(
+        serialize_with = "serialize_f32_vec",
+        deserialize_with = "deserialize_f32_vec"
+    )]
     pub r: Vec<f32>,
+    /// The `confidence` of the rating, only set with `publication_confidence`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_f32_option",
+        deserialize_with = "deserialize_f32_option"
+    )]
+    pub c: Option<f32>,
+}
+
+/// The article database as written to `article_output_path`.
+#[derive(Serialize, Debug)]
+pub struct ArticleDatabase {
+    /// `FORMAT_VERSION` of the crate that wrote the database.
+    pub format_version: u32,
+    /// Titles of the hallmarks the ratings are indexed by, those of the rater that wrote
+    /// the database.
+    pub hallmarks: Vec<String>,
+    pub publications: Vec<RatedPublication>,
+}
+
+/// Either layout of an article database on disk: versioned, or a bare array of
+/// publications as written before `FORMAT_VERSION` existed.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredArticleDatabase {
+    Versioned {
+        format_version: u32,
+        /// Missing in databases written before the hallmarks were embedded.
+        #[serde(default)]
+        hallmarks: Option<Vec<String>>,
+        publications: Vec<RatedPublication>,
+    },
+    Legacy(Vec<RatedPublication>),
+}
+
+impl ArticleDatabase {
+    pub fn new(hallmarks: Vec<String>, publications: Vec<RatedPublication>) -> Self {
+        ArticleDatabase {
+            format_version: FORMAT_VERSION,
+            hallmarks,
+            publications,
+        }
+    }
+
+    /// Reads an article database, also one written before versioning, which loads as
+    /// `format_version` 0. Fails for databases of a newer format than this crate knows.
+    /// A `.jsonl` path is read as one publication per line, and a `.gz` path is decompressed. Databases without hallmark
+    /// titles, such as JSON Lines, are taken to use `DEFAULT_HALLMARKS`.
+    pub fn load(path: &str) -> std::io::Result<ArticleDatabase> {
+        let file_contents = read_output_file(path)?;
+        if is_jsonl(path) {
+            let publications = file_contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<_, _>>()?;
+            return Ok(ArticleDatabase::new(
+                default_hallmark_titles(),
+                publications,
+            ));
+        }
+        let database = match serde_json::from_str(&file_contents)? {
+            StoredArticleDatabase::Versioned {
+                format_version,
+                hallmarks,
+                publications,
+            } => ArticleDatabase {
+                format_version,
+                hallmarks: hallmarks.unwrap_or_else(default_hallmark_titles),
+                publications,
+            },
+            StoredArticleDatabase::Legacy(publications) => ArticleDatabase {
+                format_version: 0,
+                hallmarks: default_hallmark_titles(),
+                publications,
+            },
+        };
+        check_format_version(path, database.format_version)?;
+        Ok(database)
+    }
+}
+
+/// Why an article is left out of the article database, see `DroppedPublications`.
+enum DropReason {
+    /// Declined by the id policy, by default for lacking a PMC id.
+    WithoutPmc,
+    EmptyAbstract,
+    TooFewKeywords,
+    InvalidRating,
+    NonFiniteRating,
+}
+
+/// Decides whether an article is rated and returns its id, see `Analyzer::set_article_id`.
+pub type ArticleId = Arc<dyn Fn(&Article) -> Option<String> + Send + Sync>;
+
+/// Receives the progress of each phase as `(phase, current, total)`, see
+/// `Analyzer::set_progress_callback`.
+pub type ProgressCallback = Arc<Mutex<dyn FnMut(&str, u64, u64) + Send>>;
+
+/// Progress bar of one phase, advancing per article when the total article count is
+/// known and per file otherwise. Reports to the progress callback instead, if one is set.
+struct Progress {
+    bar: indicatif::ProgressBar,
+    per_article: bool,
+    callback: Option<(ProgressCallback, &'static str, u64)>,
+    position: AtomicU64,
+}
+
+impl Progress {
+    fn hidden() -> Self {
+        Progress {
+            bar: indicatif::ProgressBar::hidden(),
+            per_article: false,
+            callback: None,
+            position: AtomicU64::new(0),
+        }
+    }
+
+    fn article_done(&self) {
+        if self.per_article {
+            self.advance(1);
+        }
+    }
+
+    fn source_done(&self, source: &InputSource) {
+        if !self.per_article {
+            self.advance(source.files.len() as u64);
+        }
+    }
+
+    fn advance(&self, n: u64) {
+        self.bar.inc(n);
+        if let Some((callback, phase, total)) = &self.callback {
+            let current = self.position.fetch_add(n, Ordering::Relaxed) + n;
+            (callback.lock().unwrap())(phase, current, *total);
+        }
+    }
+
+    fn finish(&self, message: &'static str) {
+        self.bar.finish_with_message(message);
+    }
 }
 
 impl RatedPublication {
     pub fn is_valid(&self) -> bool {
-        let mut rating_norm = 0.0;
-        for i in 0..DEFAULT_HALLMARKS.len() {
-            rating_norm += self.r[i];
-        }
+        let rating_norm: f32 = self.r.iter().sum();
 
         rating_norm > 0.95 && rating_norm < 1.05
     }
+
+    /// Whether the rating has infinite components, or NaN next to other values. A rating
+    /// that is NaN throughout is the regular result for a publication without any rated
+    /// keyword and does not count.
+    pub fn has_non_finite_rating(&self) -> bool {
+        self.r.iter().any(|v| v.is_infinite())
+            || (self.r.iter().any(|v| v.is_nan()) && !self.r.iter().all(|v| v.is_nan()))
+    }
+
+    /// The index of the hallmark with the highest rating, `None` for an empty rating.
+    pub fn dominant_hallmark(&self) -> Option<usize> {
+        self.r
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(hallmark, _)| hallmark)
+    }
+
+    /// One minus the entropy of the rating, relative to the entropy of a uniform rating: 1
+    /// for a publication about a single hallmark, 0 for one that is equally about all. A
+    /// rating with at most one hallmark is always fully confident.
+    pub fn confidence(&self) -> f32 {
+        if self.r.len() <= 1 {
+            return 1.0;
+        }
+        let entropy: f32 = self
+            .r
+            .iter()
+            .filter(|p| **p > 0.0)
+            .map(|p| -p * p.ln())
+            .sum();
+        1.0 - entropy / (self.r.len() as f32).ln()
+    }
+
+    /// Indices of the hallmarks whose rating exceeds the threshold of the same index, as
+    /// multi-label classification. Hallmarks without a threshold are never labeled.
+    pub fn labels(&self, thresholds: &[f32]) -> Vec<usize> {
+        self.r
+            .iter()
+            .zip(thresholds.iter())
+            .enumerate()
+            .filter(|(_, (rating, threshold))| rating > threshold)
+            .map(|(hallmark, _)| hallmark)
+            .collect()
+    }
+
+    /// Like `labels`, with one threshold for all hallmarks.
+    pub fn labels_above(&self, threshold: f32) -> Vec<usize> {
+        self.labels(&vec![threshold; self.r.len()])
+    }
 }
+
 impl Analyzer {
     pub fn new(lower_cutoff: f32, upper_cutoff: f32) -> Self {
+        Analyzer::with_config(AnalyzerConfig {
+            lower_cutoff,
+            upper_cutoff,
+            ..Default::default()
+        })
+    }
+
+    pub fn with_config(config: AnalyzerConfig) -> Self {
         let bar_style = indicatif::ProgressStyle::with_template(
             "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>5}/{len:5} {msg} {eta}",
         )
@@ -51,77 +356,678 @@ impl Analyzer {
         .progress_chars("##-");
         Self {
             filenames: vec![],
-            lower_cutoff,
-            upper_cutoff,
+            tokenizer: Tokenizer::new(&config.tokenizer).expect("invalid protected pattern"),
+            config,
+            summary: RunSummary::default(),
             keyword_candidates: HashMap::new(),
+            n_scanned_articles: 0,
+            total_articles: None,
+            rating_total_articles: None,
+            article_counts: HashMap::new(),
+            token_cache: vec![],
+            cooccurrence_record: CooccurrenceRecord::default(),
             bar_style,
+            progress_callback: None,
+            article_id: None,
+            cancellation_flag: None,
+            malformed_articles: Mutex::new(HashSet::new()),
+            short_articles: Mutex::new(HashSet::new()),
+            tuned_lower_bound: None,
+        }
+    }
+
+    /// Runs all phases, on a thread pool of `threads` threads if configured and on the
+    /// global rayon pool otherwise. Returns `AnalyzerError::Cancelled` if the cancellation
+    /// flag was set, see `set_cancellation_flag`.
+    pub fn run(&mut self) -> Result<(), AnalyzerError> {
+        self.in_thread_pool(|analyzer| analyzer.run_phases())
+    }
+
+    /// Trains on the input files and rates their publications like `run`, but returns the
+    /// rating database and the rated publications instead of writing them. Nothing is
+    /// printed and no file is written: the run is quiet, and `candidate_output_path`, the
+    /// other intermediate outputs, `shard_output`, `stream_article_output` and the
+    /// diagnostic reports are ignored. `ensemble_models` is ignored too, so a model is
+    /// always trained.
+    pub fn run_in_memory(
+        &mut self,
+    ) -> Result<(FullRunOutput, Vec<RatedPublication>), AnalyzerError> {
+        let config = self.config.clone();
+        self.config.quiet = true;
+        self.config.candidate_output_path = None;
+        self.config.vocabulary_output_path = None;
+        self.config.matrix_stats_output_path = None;
+        self.config.seed_rating_output_path = None;
+        self.config.variant_report = None;
+        self.config.log_empty_abstracts = false;
+        let result = self.in_thread_pool(|analyzer| {
+            analyzer.prepare_inputs()?;
+            let mut analyzer_data = analyzer.build_analyzer_data()?;
+            analyzer.compute_ratings(&mut analyzer_data)?;
+            analyzer.count_rating_articles()?;
+            let (publications, distribution) =
+                analyzer.collect_rated_publications(&analyzer_data)?;
+            analyzer.check_cancelled()?;
+            analyzer.summary.corpus_distribution = distribution;
+            Ok((analyzer_data.rating_output(), publications))
+        });
+        self.config = config;
+        result
+    }
+
+    fn in_thread_pool<T: Send>(&mut self, phases: impl FnOnce(&mut Self) -> T + Send) -> T {
+        match self.config.threads {
+            Some(n_threads) => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(n_threads)
+                    .build()
+                    .expect("could not create the thread pool");
+                pool.install(|| phases(self))
+            }
+            None => phases(self),
         }
     }
 
-    pub fn run(&mut self) {
-        self.detect_input_files();
-        let mut analyzer_data = self.analyze_dataset();
-        self.build_relations_matrix(&mut analyzer_data);
-        analyzer_data.print();
-        analyzer_data.compute_keyword_ratings();
+    fn run_phases(&mut self) -> Result<(), AnalyzerError> {
+        self.prepare_inputs()?;
+        if !self.config.ensemble_models.is_empty() {
+            let ensemble = Ensemble::load(&self.config.ensemble_models, &self.config)?;
+            report(
+                &self.config,
+                &format!(
+                    "Loaded {} rating databases for the ensemble.",
+                    ensemble.n_models()
+                ),
+            );
+            self.rate_publications(&ensemble)?;
+            self.print_summary();
+            return Ok(());
+        }
+        let mut analyzer_data = self.build_analyzer_data()?;
+        if let Some(path) = &self.config.keyword_index_output_path {
+            analyzer_data.write_keyword_index(path).unwrap();
+        }
+        if let Some(path) = &self.config.matrix_stats_output_path {
+            analyzer_data.write_matrix_stats(path).unwrap();
+            report(
+                &self.config,
+                &format!("Wrote the relations matrix statistics to {}.", path),
+            );
+        }
+        if let Some(path) = &self.config.shard_output {
+            analyzer_data.save_shard(path).unwrap();
+            report(
+                &self.config,
+                &format!("Wrote the unnormalized relations matrix to {}.", path),
+            );
+            return Ok(());
+        }
+        self.compute_ratings(&mut analyzer_data)?;
+        if self.config.hallmark_correlations {
+            analyzer_data.print_hallmark_correlations();
+        }
+        if let Some((a, b)) = self.config.shared_keywords {
+            analyzer_data.print_shared_top_keywords(a, b, self.config.top_keywords);
+        }
         analyzer_data.write_rating_output();
-        self.rate_publications(analyzer_data);
+        self.rate_publications(&analyzer_data)?;
+        self.print_summary();
+        Ok(())
     }
 
-    fn rate_publications(&self, analyzer: AnalyzerData) {
-        let mut article_ratings = vec![];
-        let bar = indicatif::ProgressBar::new(self.filenames.len() as u64);
-        bar.set_message("Rating the article database.");
+    /// Collects the input files and applies `file_limit`.
+    fn prepare_inputs(&mut self) -> Result<(), AnalyzerError> {
+        if self.config.input_files.is_empty() {
+            self.detect_input_files();
+        } else {
+            self.filenames = self.config.input_files.clone();
+        }
+        if let Some(limit) = self.config.file_limit {
+            if self.filenames.len() > limit {
+                self.filenames.truncate(limit);
+                report(
+                    &self.config,
+                    &format!("Limited the input to the first {} files.", limit),
+                );
+            }
+        }
+        self.check_cancelled()
+    }
+
+    /// The unnormalized relations matrix, built from the input files or loaded from shards.
+    fn build_analyzer_data(&mut self) -> Result<AnalyzerData, AnalyzerError> {
+        if self.config.shard_inputs.is_empty() {
+            let mut analyzer_data = self.analyze_dataset()?;
+            if let Some(path) = &self.config.vocabulary_output_path {
+                analyzer_data.write_vocabulary(path).unwrap();
+                report(&self.config, &format!("Wrote the vocabulary to {}.", path));
+            }
+            if self.records_cooccurrences() {
+                self.build_recorded_relations_matrix(&mut analyzer_data);
+                self.cooccurrence_record = CooccurrenceRecord::default();
+            } else {
+                self.build_relations_matrix(&mut analyzer_data)?;
+                self.token_cache = vec![];
+            }
+            self.summary.n_sparse_articles = analyzer_data.n_sparse_articles();
+            self.check_cancelled()?;
+            Ok(analyzer_data)
+        } else {
+            self.load_shards()
+        }
+    }
+
+    /// Normalizes the relations matrix and computes the keyword ratings.
+    fn compute_ratings(&mut self, analyzer_data: &mut AnalyzerData) -> Result<(), AnalyzerError> {
+        self.record_vocabulary_size("of the relations matrix", analyzer_data.n_keywords());
+        if cfg!(debug_assertions) || self.config.validate_ratings {
+            analyzer_data.check_symmetry()?;
+        }
+        self.summary.connectivity = Some(analyzer_data.connectivity());
+        analyzer_data.divide_rows_by_diagonal();
+        if !self.config.quiet {
+            analyzer_data.print();
+        }
+        analyzer_data.compute_keyword_ratings()?;
+        self.summary.unmatched_seed_terms = analyzer_data.unmatched_seed_terms().to_vec();
+        self.check_finite_keyword_ratings(analyzer_data)
+    }
+
+    /// Reports NaN or infinite keyword ratings and fails for `NonFiniteHandling::Fail`.
+    fn check_finite_keyword_ratings(
+        &self,
+        analyzer_data: &AnalyzerData,
+    ) -> Result<(), AnalyzerError> {
+        let non_finite = analyzer_data.non_finite_ratings();
+        if non_finite.is_empty() {
+            return Ok(());
+        }
+        report(
+            &self.config,
+            &format!(
+                "Warning: {} keyword ratings are NaN or infinite.",
+                non_finite.len()
+            ),
+        );
+        for (keyword, hallmark, value) in non_finite.iter().take(10) {
+            report(
+                &self.config,
+                &format!(
+                    "  {} of '{}': {}",
+                    DEFAULT_HALLMARKS[*hallmark].title, keyword, value
+                ),
+            );
+        }
+        if self.config.non_finite_ratings == NonFiniteHandling::Fail {
+            let (keyword, hallmark, value) = non_finite[0].clone();
+            return Err(AnalyzerError::NonFiniteRatings {
+                n_keyword_values: non_finite.len(),
+                n_publications: 0,
+                first_keyword_value: Some((keyword, DEFAULT_HALLMARKS[hallmark].title, value)),
+            });
+        }
+        Ok(())
+    }
+
+    /// Progress bar of a training phase, which processes `filenames`.
+    fn progress(&self, message: &'static str) -> Progress {
+        self.progress_over(message, self.filenames.len(), self.total_articles)
+    }
+
+    fn progress_over(
+        &self,
+        message: &'static str,
+        n_files: usize,
+        n_articles: Option<usize>,
+    ) -> Progress {
+        let length = n_articles.unwrap_or(n_files) as u64;
+        let per_article = n_articles.is_some();
+        if let Some(callback) = &self.progress_callback {
+            return Progress {
+                callback: Some((callback.clone(), message, length)),
+                per_article,
+                ..Progress::hidden()
+            };
+        }
+        if self.config.quiet {
+            return Progress::hidden();
+        }
+        let bar = indicatif::ProgressBar::new(length);
+        bar.set_message(message);
         bar.set_style(self.bar_style.clone());
-        for file in self.filenames.iter() {
-            let file_contents: String = fs::read_to_string(file).unwrap();
-            let articles: Vec<article::Article> = serde_json::from_str(&file_contents).unwrap();
-            for article in articles.iter() {
-                if article.pmc != "" {
-                    let words =
-                        Analyzer::split_abstract_into_words(article.paper_abstract.clone(), false);
-                    let article_rating: RatedPublication =
-                        analyzer.rate_article_keywords(words, article.pmc.clone());
-                    if article_rating.is_valid() {
-                        article_ratings.push(article_rating);
+        Progress {
+            bar,
+            per_article,
+            ..Progress::hidden()
+        }
+    }
+
+    /// Every file is its own source, unless `concatenated_input` joins them into one.
+    fn input_sources(&self, filenames: &[String]) -> Vec<InputSource> {
+        if self.config.concatenated_input {
+            vec![InputSource {
+                files: filenames.to_vec(),
+            }]
+        } else {
+            filenames
+                .iter()
+                .map(|f| InputSource {
+                    files: vec![f.clone()],
+                })
+                .collect()
+        }
+    }
+
+    /// The files whose publications are rated: `rating_input_files` if configured,
+    /// otherwise the training files.
+    pub fn rating_filenames(&self) -> &[String] {
+        if self.config.rating_input_files.is_empty() {
+            &self.filenames
+        } else {
+            &self.config.rating_input_files
+        }
+    }
+
+    fn record_vocabulary_size(&mut self, stage: &str, size: usize) {
+        self.summary
+            .vocabulary_sizes
+            .push((stage.to_string(), size));
+    }
+
+    /// Reports the progress of every phase to `callback` as `(phase, current, total)`
+    /// instead of drawing progress bars, e.g. when embedding the analyzer in a GUI. The
+    /// callback is also called in quiet mode, and from worker threads during rating, where
+    /// consecutive values may arrive slightly out of order.
+    pub fn set_progress_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&str, u64, u64) + Send + 'static,
+    {
+        self.progress_callback = Some(Arc::new(Mutex::new(callback)));
+    }
+
+    /// Replaces the check which articles are rated and under which id. `id` returns `None`
+    /// for articles to leave out. By default only articles with a PMC id are rated, under
+    /// that id. Called from worker threads during rating.
+    pub fn set_article_id<F>(&mut self, id: F)
+    where
+        F: Fn(&Article) -> Option<String> + Send + Sync + 'static,
+    {
+        self.article_id = Some(Arc::new(id));
+    }
+
+    /// Stops every phase between two input files once `flag` is set, e.g. from another
+    /// thread when the user aborts. `run` then returns `AnalyzerError::Cancelled`. Output
+    /// files of the phase that was interrupted may be incomplete.
+    pub fn set_cancellation_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancellation_flag = Some(flag);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    fn check_cancelled(&self) -> Result<(), AnalyzerError> {
+        if self.is_cancelled() {
+            Err(AnalyzerError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn print_summary(&mut self) {
+        self.summary.n_malformed_articles = self.malformed_articles.lock().unwrap().len();
+        self.summary.n_short_articles = self.short_articles.lock().unwrap().len();
+        self.summary.print();
+    }
+
+    pub fn summary(&self) -> &RunSummary {
+        &self.summary
+    }
+
+    fn rate_publications<R: PublicationRater>(&mut self, rater: &R) -> Result<(), AnalyzerError> {
+        self.count_rating_articles()?;
+        let path = &self.config.article_output_path;
+        let mut output = OutputFile::create(path).unwrap();
+        let distribution = if is_jsonl(path) {
+            self.write_rated_publications_jsonl(rater, &mut output)
+        } else {
+            self.write_rated_publications(rater, &mut output)
+        }
+        .map_err(|e| match e.downcast::<InputError>() {
+            Ok(e) => AnalyzerError::Input(e),
+            Err(e) => panic!("could not write {}: {}", path, e),
+        })?;
+        output.finish().unwrap();
+        self.check_cancelled()?;
+        report(
+            &self.config,
+            &format!("Rated a total of {} articles.", distribution.n_publications),
+        );
+        let n_non_finite = distribution.dropped.non_finite_rating;
+        self.summary.corpus_distribution = distribution;
+        if n_non_finite > 0 && self.config.non_finite_ratings == NonFiniteHandling::Fail {
+            return Err(AnalyzerError::NonFiniteRatings {
+                n_keyword_values: 0,
+                n_publications: n_non_finite,
+                first_keyword_value: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Rates the publications of all input files and writes them as an `ArticleDatabase` to
+    /// `writer`. Returns the hallmark distribution of the rated publications. An input file
+    /// that cannot be read fails with an error of kind `Other` wrapping the `InputError`.
+    pub fn write_rated_publications<R: PublicationRater, W: Write>(
+        &self,
+        rater: &R,
+        mut writer: W,
+    ) -> std::io::Result<CorpusDistribution> {
+        if !self.config.stream_article_output {
+            let (article_ratings, distribution) = self
+                .collect_rated_publications(rater)
+                .map_err(std::io::Error::other)?;
+            let database = ArticleDatabase::new(rater.hallmark_titles(), article_ratings);
+            serde_json::to_writer(&mut writer, &database)?;
+            writer.flush()?;
+            return Ok(distribution);
+        }
+        write!(
+            writer,
+            "{{\"format_version\":{},\"hallmarks\":{},\"publications\":[",
+            FORMAT_VERSION,
+            serde_json::to_string(&rater.hallmark_titles())?
+        )?;
+        let mut is_first = true;
+        let distribution = self.stream_rated_publications(rater, |article_rating| {
+            if !is_first {
+                writer.write_all(b",")?;
+            }
+            is_first = false;
+            serde_json::to_writer(&mut writer, article_rating)?;
+            Ok(())
+        })?;
+        writer.write_all(b"]}")?;
+        writer.flush()?;
+        Ok(distribution)
+    }
+
+    /// Like `write_rated_publications`, but writes JSON Lines: one publication per line, as
+    /// soon as it is rated. There is no enclosing object, so no `format_version` either.
+    pub fn write_rated_publications_jsonl<R: PublicationRater, W: Write>(
+        &self,
+        rater: &R,
+        mut writer: W,
+    ) -> std::io::Result<CorpusDistribution> {
+        let distribution = self.stream_rated_publications(rater, |article_rating| {
+            serde_json::to_writer(&mut writer, article_rating)?;
+            writer.write_all(b"\n")
+        })?;
+        writer.flush()?;
+        Ok(distribution)
+    }
+
+    /// Rates the publications of all input files one source at a time and hands each to
+    /// `write` right away.
+    fn stream_rated_publications<R: PublicationRater>(
+        &self,
+        rater: &R,
+        mut write: impl FnMut(&RatedPublication) -> std::io::Result<()>,
+    ) -> std::io::Result<CorpusDistribution> {
+        let filenames = self.rating_filenames();
+        let progress = self.progress_over(
+            "Rating the article database.",
+            filenames.len(),
+            self.rating_total_articles,
+        );
+        let mut distribution = CorpusDistribution::new(rater.hallmark_titles());
+        for source in self.input_sources(filenames).iter() {
+            if self.is_cancelled() {
+                break;
+            }
+            let article_ratings = self
+                .rate_one_input_file(rater, source, &progress, &mut distribution.dropped)
+                .map_err(std::io::Error::other)?;
+            for article_rating in article_ratings {
+                write(&article_rating)?;
+                distribution.add(&article_rating.r);
+            }
+            progress.source_done(source);
+        }
+        progress.finish("Done rating publications.");
+        Ok(distribution)
+    }
+
+    /// Rates the publications of all input files into memory. Also returns their hallmark
+    /// distribution.
+    fn collect_rated_publications<R: PublicationRater>(
+        &self,
+        rater: &R,
+    ) -> Result<(Vec<RatedPublication>, CorpusDistribution), InputError> {
+        let filenames = self.rating_filenames();
+        let progress = self.progress_over(
+            "Rating the article database.",
+            filenames.len(),
+            self.rating_total_articles,
+        );
+        let mut distribution = CorpusDistribution::new(rater.hallmark_titles());
+        let mut article_ratings = vec![];
+        for source in self.input_sources(filenames).iter() {
+            if self.is_cancelled() {
+                break;
+            }
+            article_ratings.extend(self.rate_one_input_file(
+                rater,
+                source,
+                &progress,
+                &mut distribution.dropped,
+            )?);
+            progress.source_done(source);
+        }
+        for article_rating in article_ratings.iter() {
+            distribution.add(&article_rating.r);
+        }
+        progress.finish("Done rating publications.");
+        Ok((article_ratings, distribution))
+    }
+
+    /// Lazily rates the publications of all input files. Only one input file is held in
+    /// memory at a time. An input file that cannot be read yields its error in place of its
+    /// publications.
+    pub fn rated_publications<'a, R: PublicationRater>(
+        &'a self,
+        rater: &'a R,
+    ) -> impl Iterator<Item = Result<RatedPublication, InputError>> + 'a {
+        let progress = Progress::hidden();
+        let mut dropped = DroppedPublications::default();
+        let sources = self.input_sources(self.rating_filenames());
+        sources.into_iter().flat_map(move |source| {
+            match self.rate_one_input_file(rater, &source, &progress, &mut dropped) {
+                Ok(article_ratings) => article_ratings.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            }
+        })
+    }
+
+    /// Rates the articles of one input source in parallel, keeping their order.
+    fn rate_one_input_file<R: PublicationRater>(
+        &self,
+        rater: &R,
+        source: &InputSource,
+        progress: &Progress,
+        dropped: &mut DroppedPublications,
+    ) -> Result<Vec<RatedPublication>, InputError> {
+        let articles = self.read_source(source)?;
+        let outcomes: Vec<Result<RatedPublication, DropReason>> = articles
+            .par_iter()
+            .map(|article| {
+                progress.article_done();
+                self.rate_article(rater, article)
+            })
+            .collect();
+        let mut article_ratings = vec![];
+        for (article, outcome) in articles.iter().zip(outcomes) {
+            match outcome {
+                Ok(article_rating) => article_ratings.push(article_rating),
+                Err(DropReason::WithoutPmc) => dropped.without_pmc += 1,
+                Err(DropReason::EmptyAbstract) => {
+                    dropped.empty_abstract += 1;
+                    if self.config.log_empty_abstracts {
+                        println!(
+                            "Article {} in {} has no tokens after tokenization.",
+                            article.pmc,
+                            source.name()
+                        );
                     }
                 }
+                Err(DropReason::TooFewKeywords) => dropped.too_few_keywords += 1,
+                Err(DropReason::InvalidRating) => dropped.invalid_rating += 1,
+                Err(DropReason::NonFiniteRating) => {
+                    dropped.non_finite_rating += 1;
+                    report(
+                        &self.config,
+                        &format!(
+                            "Warning: article {} in {} has a NaN or infinite rating.",
+                            article.pmc,
+                            source.name()
+                        ),
+                    );
+                }
             }
-            bar.inc(1);
         }
+        self.warn_if_unusable(source, "rate", article_ratings.len());
+        Ok(article_ratings)
+    }
 
-        bar.finish_with_message("Done rating publications.");
+    fn rate_article<R: PublicationRater>(
+        &self,
+        rater: &R,
+        article: &article::Article,
+    ) -> Result<RatedPublication, DropReason> {
+        let id = match &self.article_id {
+            Some(article_id) => article_id(article),
+            None if article.pmc.is_empty() => None,
+            None => Some(article.pmc.clone()),
+        };
+        let Some(id) = id else {
+            return Err(DropReason::WithoutPmc);
+        };
+        let words = self.tokenizer.tokenize(&article.paper_abstract);
+        if words.is_empty() {
+            return Err(DropReason::EmptyAbstract);
+        }
+        if !rater.has_enough_keywords(&words) {
+            return Err(DropReason::TooFewKeywords);
+        }
+        let mut article_rating = rater.rate_article_keywords(words, id);
+        if article_rating.has_non_finite_rating() {
+            return Err(DropReason::NonFiniteRating);
+        }
+        if article_rating.is_valid() {
+            if self.config.publication_confidence {
+                article_rating.c = Some(article_rating.confidence());
+            }
+            Ok(article_rating)
+        } else {
+            Err(DropReason::InvalidRating)
+        }
+    }
 
-        println!("Rated a total of {} articles.", article_ratings.len());
-        let output_json = serde_json::to_string(&article_ratings).unwrap();
-        let mut file = std::fs::File::create("article_database.json".to_string()).unwrap();
-        file.write_all(output_json.as_bytes()).unwrap();
+    fn build_relations_matrix(&self, analyzer: &mut AnalyzerData) -> Result<(), InputError> {
+        let progress = self.progress("Building Relations Matrix");
+        for (index, source) in self.input_sources(&self.filenames).iter().enumerate() {
+            if self.is_cancelled() {
+                break;
+            }
+            let mut n_usable = 0;
+            let mut count_article = |words: &[(String, usize)]| {
+                if analyzer.update_with_article_data(words) > 0 {
+                    n_usable += 1;
+                }
+                progress.article_done();
+            };
+            match self.token_cache.get(index) {
+                Some(cached) => cached.iter().for_each(|words| count_article(words)),
+                None => {
+                    for article in self.read_source(source)?.iter() {
+                        count_article(&self.tokenizer.token_counts(&article.paper_abstract));
+                    }
+                }
+            }
+            self.warn_if_unusable(source, "build the relations matrix", n_usable);
+            progress.source_done(source);
+        }
+        progress.finish("Done building the relations matrix.");
+        Ok(())
     }
 
-    fn build_relations_matrix(&self, analyzer: &mut AnalyzerData) {
-        let bar = indicatif::ProgressBar::new(self.filenames.len() as u64);
-        bar.set_message("Building Relations Matrix");
-        bar.set_style(self.bar_style.clone());
-        for file in self.filenames.iter() {
-            let file_contents: String = fs::read_to_string(file).unwrap();
-            let articles: Vec<article::Article> = serde_json::from_str(&file_contents).unwrap();
-            for article in articles.iter() {
-                let words =
-                    Analyzer::split_abstract_into_words(article.paper_abstract.clone(), true);
-                analyzer.update_with_article_data(&words);
+    /// Builds the relations matrix from the co-occurrences recorded by the candidate scan,
+    /// keeping the pairs of which both candidates survived the cutoffs.
+    fn build_recorded_relations_matrix(&self, analyzer: &mut AnalyzerData) {
+        let record = &self.cooccurrence_record;
+        let mut keyword_indices = vec![None; record.candidate_ids.len()];
+        for (candidate, id) in record.candidate_ids.iter() {
+            keyword_indices[*id as usize] = analyzer.keyword_index(candidate);
+        }
+        let entries: Vec<(usize, usize, f32)> = record
+            .pairs
+            .iter()
+            .filter_map(|((a, b), value)| {
+                let i = keyword_indices[*a as usize]?;
+                let j = keyword_indices[*b as usize]?;
+                Some((i, j, *value))
+            })
+            .collect();
+        let mut n_relevant_words = vec![];
+        let sources = self.input_sources(&self.filenames);
+        for (source, articles) in sources.iter().zip(record.articles.iter()) {
+            let mut n_usable = 0;
+            for ids in articles.iter() {
+                let n_relevant = ids
+                    .iter()
+                    .filter(|id| keyword_indices[**id as usize].is_some())
+                    .count();
+                if n_relevant > 0 {
+                    n_usable += 1;
+                }
+                n_relevant_words.push(n_relevant);
             }
-            bar.inc(1);
+            self.warn_if_unusable(source, "build the relations matrix", n_usable);
+        }
+        analyzer.add_recorded_cooccurrences(&entries, &n_relevant_words);
+        report(
+            &self.config,
+            "Done building the relations matrix from the recorded co-occurrences.",
+        );
+    }
+
+    fn load_shards(&self) -> Result<AnalyzerData, AnalyzerError> {
+        let load = |path: &String| {
+            AnalyzerData::load_shard(path, &self.config).map_err(|source| InputError::Io {
+                file: path.clone(),
+                source,
+            })
+        };
+        let mut shards = self.config.shard_inputs.iter();
+        let mut analyzer_data = load(shards.next().unwrap())?;
+        for path in shards {
+            analyzer_data.merge(load(path)?);
         }
-        analyzer.divide_rows_by_diagonal();
-        bar.finish_with_message("Done building the relations matrix.");
+        report(
+            &self.config,
+            &format!("Merged {} shards.", self.config.shard_inputs.len()),
+        );
+        Ok(analyzer_data)
     }
 
     fn detect_input_files(&mut self) {
-        let mut counter = 1;
+        let mut counter = self.config.first_input_index;
         loop {
-            let fname = format!("results_pubmed24n{:0>4}.xml.json", counter.clone());
+            let fname = format_input_filename(&self.config.input_file_template, counter);
             let file_exists = std::path::Path::new(&fname).exists();
-            if file_exists {
+            if file_exists && !self.filenames.contains(&fname) {
                 self.filenames.push(fname.clone());
                 counter += 1;
             } else {
@@ -130,84 +1036,467 @@ impl Analyzer {
         }
     }
 
-    fn analyze_dataset(&mut self) -> AnalyzerData {
-        let bar = indicatif::ProgressBar::new(self.filenames.len() as u64);
-        bar.set_message("Searching for possible keywords...");
-        bar.set_style(self.bar_style.clone());
-        for file in self.filenames.clone().iter() {
-            self.analyze_one_input_file(file.clone());
-            bar.inc(1);
+    fn analyze_dataset(&mut self) -> Result<AnalyzerData, AnalyzerError> {
+        let progress = self.progress("Searching for possible keywords...");
+        for source in self.input_sources(&self.filenames).iter() {
+            if self.is_cancelled() {
+                break;
+            }
+            self.analyze_one_input_file(source, &progress)?;
+            progress.source_done(source);
+        }
+        self.check_cancelled()?;
+        if self.config.article_progress {
+            self.total_articles = Some(self.n_scanned_articles);
         }
 
-        bar.finish_with_message("Done with computation.");
-        print!(
-            "Found a total of {} words.",
-            self.keyword_candidates.len() as u32
+        progress.finish("Done with computation.");
+        report(
+            &self.config,
+            &format!("Found a total of {} words.", self.keyword_candidates.len()),
         );
+        self.record_vocabulary_size("of the keyword candidates", self.keyword_candidates.len());
+        if let Some(path) = &self.config.candidate_output_path {
+            self.write_candidate_frequencies(path).unwrap();
+        }
+        if let Some(n_clusters) = self.config.variant_report {
+            self.print_keyword_variants(n_clusters);
+        }
 
+        if let Some(target) = self.config.target_vocabulary_size {
+            self.tune_lower_cutoff(target);
+        }
+        let counts = self.keyword_candidates.values();
+        let (min_count, max_count) = (counts.clone().min().copied(), counts.max().copied());
+        let n_candidates = self.keyword_candidates.len();
         self.purge_keyword_array();
+        if self.keyword_candidates.is_empty() {
+            let (lower_bound, upper_bound) = self.cutoff_bounds();
+            return Err(AnalyzerError::EmptyVocabulary {
+                n_candidates,
+                lower_bound,
+                upper_bound,
+                min_count: min_count.unwrap_or(0),
+                max_count: max_count.unwrap_or(0),
+            });
+        }
+        report(
+            &self.config,
+            &format!(
+                "{} keywords survived the cutoffs.",
+                self.keyword_candidates.len()
+            ),
+        );
+        self.record_vocabulary_size("after the cutoffs", self.keyword_candidates.len());
+        if let Some(max_size) = self.config.max_vocabulary_size {
+            self.cap_vocabulary(max_size);
+        }
+        self.purge_token_cache();
         let keywords: Vec<String> = self
             .keyword_candidates
             .iter()
             .map(|k| k.0.clone())
             .collect();
-        AnalyzerData::new(self.keyword_candidates.len(), &keywords)
+        let mut analyzer_data =
+            AnalyzerData::new(self.keyword_candidates.len(), &keywords, &self.config);
+        analyzer_data.set_document_frequencies(&self.keyword_candidates, self.n_scanned_articles);
+        Ok(analyzer_data)
+    }
+
+    /// Groups the keyword candidates by their `variant_key`, returning every group with more
+    /// than one member. Groups are sorted by size, then by their summed candidate counts.
+    pub fn keyword_variant_clusters(&self) -> Vec<(String, Vec<String>)> {
+        let mut clusters: HashMap<String, Vec<String>> = HashMap::new();
+        for keyword in self.keyword_candidates.keys() {
+            clusters
+                .entry(variant_key(keyword))
+                .or_default()
+                .push(keyword.clone());
+        }
+        let count = |members: &[String]| -> usize {
+            members.iter().map(|m| self.keyword_candidates[m]).sum()
+        };
+        let mut ret: Vec<(String, Vec<String>)> = clusters
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(key, mut members)| {
+                members.sort();
+                (key, members)
+            })
+            .collect();
+        ret.sort_by(|a, b| {
+            b.1.len()
+                .cmp(&a.1.len())
+                .then_with(|| count(&b.1).cmp(&count(&a.1)))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        ret
     }
 
-    fn analyze_one_input_file(&mut self, filename: String) {
-        let file_contents: String = fs::read_to_string(filename).unwrap();
-        let articles: Vec<article::Article> = serde_json::from_str(&file_contents).unwrap();
+    fn print_keyword_variants(&self, n_clusters: usize) {
+        let clusters = self.keyword_variant_clusters();
+        let n_variants: usize = clusters.iter().map(|c| c.1.len()).sum();
+        println!(
+            "{} keyword candidates fall into {} variant clusters.",
+            n_variants,
+            clusters.len()
+        );
+        for (key, members) in clusters.iter().take(n_clusters) {
+            let listed: Vec<String> = members
+                .iter()
+                .map(|m| format!("{} ({})", m, self.keyword_candidates[m]))
+                .collect();
+            println!("  {}: {}", key, listed.join(", "));
+        }
+    }
+
+    fn analyze_one_input_file(
+        &mut self,
+        source: &InputSource,
+        progress: &Progress,
+    ) -> Result<(), InputError> {
+        let articles = self.read_source(source)?;
+        let mut n_usable = 0;
+        let mut cached = vec![];
+        let mut recorded = vec![];
         for article in articles.iter() {
-            self.process_abstract(article.paper_abstract.clone());
+            let is_usable = if self.records_cooccurrences() {
+                let words = self.tokenizer.token_counts(&article.paper_abstract);
+                recorded.push(self.record_cooccurrences(&words));
+                self.count_candidates(words.iter().map(|(word, _)| word))
+            } else if self.config.cache_tokens {
+                let words = self.tokenizer.token_counts(&article.paper_abstract);
+                let is_usable = self.count_candidates(words.iter().map(|(word, _)| word));
+                cached.push(words);
+                is_usable
+            } else {
+                self.process_abstract(article.paper_abstract.clone())
+            };
+            if is_usable {
+                n_usable += 1;
+            }
+            progress.article_done();
+        }
+        if self.records_cooccurrences() {
+            self.cooccurrence_record.articles.push(recorded);
+        } else if self.config.cache_tokens {
+            self.token_cache.push(cached);
         }
+        self.warn_if_unusable(source, "scan for keywords", n_usable);
+        self.n_scanned_articles += articles.len();
+        self.article_counts.insert(source.name(), articles.len());
+        Ok(())
+    }
+
+    /// Counts the tokens of one abstract as keyword candidates. Returns false if the
+    /// abstract yields no tokens.
+    fn process_abstract(&mut self, paper_abstract: String) -> bool {
+        let words = self.tokenizer.unique_tokens(&paper_abstract);
+        self.count_candidates(words.iter())
     }
 
-    fn process_abstract(&mut self, paper_abstract: String) {
-        let words = Analyzer::split_abstract_into_words(paper_abstract, true);
+    /// Counts the distinct tokens of one abstract as keyword candidates. Returns false if
+    /// there are none.
+    fn count_candidates<'a>(&mut self, words: impl Iterator<Item = &'a String>) -> bool {
+        let mut is_usable = false;
         for word in words {
             let counter = self.keyword_candidates.entry(word.to_string()).or_insert(0);
             *counter += 1;
+            is_usable = true;
         }
+        is_usable
     }
 
-    pub fn split_abstract_into_words(paper_abstract: String, dedupe: bool) -> Vec<String> {
-        let re = Regex::new(r#"[.?,;()!\/'"%=]"#).unwrap();
-        let cleared = re.replace_all(&paper_abstract, " ").to_string().to_lowercase();
-        let mut ret: Vec<String> = cleared.split_whitespace().map(|w| Analyzer::clean_keyword(w.to_string())).collect();
-        ret.retain(|w| w.len() > 4);
-        ret.sort();
-        if dedupe {
-            ret.dedup();
+    /// Whether the candidate scan records the co-occurrences for `single_pass`. Only the
+    /// two-pass build can leave out articles for `min_relevant_keywords`.
+    fn records_cooccurrences(&self) -> bool {
+        self.config.single_pass && self.config.min_relevant_keywords == 0
+    }
+
+    /// Adds what one abstract contributes to the relations matrix to the co-occurrence
+    /// record, as `AnalyzerData::update_with_article_data` would for a vocabulary of all
+    /// candidates. Returns the candidate ids of the abstract.
+    fn record_cooccurrences(&mut self, words: &[(String, usize)]) -> Vec<u32> {
+        let record = &mut self.cooccurrence_record;
+        let ids: Vec<u32> = words
+            .iter()
+            .map(|(word, _)| {
+                let next_id = record.candidate_ids.len() as u32;
+                *record.candidate_ids.entry(word.clone()).or_insert(next_id)
+            })
+            .collect();
+        let weighting = self.config.cooccurrence_weighting;
+        let first_partner = if self.config.exclude_self_pairs { 1 } else { 0 };
+        for (i, (_, count)) in words.iter().enumerate() {
+            for (j, (_, partner_count)) in words.iter().enumerate().skip(i + first_partner) {
+                let increment = cooccurrence_increment(
+                    &self.config,
+                    i == j,
+                    *count,
+                    weighting.weight(*count),
+                    weighting.weight(*partner_count),
+                );
+                let pair = (ids[i].min(ids[j]), ids[i].max(ids[j]));
+                *record.pairs.entry(pair).or_insert(0.0) += increment;
+            }
+        }
+        ids
+    }
+
+    /// Drops the tokens that did not survive the cutoffs from the token cache, as they can
+    /// never enter the relations matrix.
+    fn purge_token_cache(&mut self) {
+        let vocabulary = &self.keyword_candidates;
+        for words in self.token_cache.iter_mut().flatten() {
+            words.retain(|(word, _)| vocabulary.contains_key(word));
         }
-        ret
     }
 
-    pub fn clean_keyword(in_word: String) -> String {
-        let mut ret = in_word.clone().to_string();
-        let mut has_changed = true;
-        while has_changed {
-            has_changed = false;
-            if ret.len() > 4 {
-            let first_char = ret.chars().next().unwrap();
-            if first_char == '-' {
-                ret.remove(0);
-                has_changed = true;
+    /// Sets the article total of the rating phase for `article_progress`. Sources that were
+    /// not read by the candidate scan are counted in a pre-pass.
+    fn count_rating_articles(&mut self) -> Result<(), InputError> {
+        if self.config.article_progress {
+            let sources = self.input_sources(self.rating_filenames());
+            self.rating_total_articles = Some(self.count_articles(&sources)?);
+        }
+        Ok(())
+    }
+
+    /// The number of articles of `sources`, reading only those whose count was not recorded
+    /// yet.
+    fn count_articles(&mut self, sources: &[InputSource]) -> Result<usize, InputError> {
+        let mut total = 0;
+        for source in sources.iter() {
+            if self.is_cancelled() {
+                break;
             }
-            let last_char: char = ret.chars().last().unwrap();
-            if last_char == '-' {
-                ret.pop();
-                has_changed = true;
+            let name = source.name();
+            if !self.article_counts.contains_key(&name) {
+                let n_articles = self.read_source(source)?.len();
+                self.article_counts.insert(name.clone(), n_articles);
             }
+            total += self.article_counts[&name];
+        }
+        Ok(total)
+    }
+
+    /// Reads the articles of `source`, failing on the first malformed article unless
+    /// `skip_malformed_articles` is set. Skipped articles are reported once per run, however
+    /// often the source is read. Articles shorter than `min_abstract_length` are left out.
+    fn read_source(&self, source: &InputSource) -> Result<Vec<Article>, InputError> {
+        let articles = self.read_source_articles(source)?;
+        let min_length = self.config.min_abstract_length;
+        if min_length == 0 {
+            return Ok(articles);
+        }
+        let mut short = self.short_articles.lock().unwrap();
+        Ok(articles
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, article)| {
+                if article.paper_abstract.chars().count() < min_length {
+                    short.insert((source.name(), index));
+                    None
+                } else {
+                    Some(article)
+                }
+            })
+            .collect())
+    }
+
+    fn read_source_articles(&self, source: &InputSource) -> Result<Vec<Article>, InputError> {
+        if !self.config.skip_malformed_articles {
+            return source.read();
+        }
+        let (articles, malformed) = source.read_skipping_malformed()?;
+        let mut skipped = self.malformed_articles.lock().unwrap();
+        for article in malformed {
+            if skipped.insert((source.name(), article.index)) {
+                report(
+                    &self.config,
+                    &format!(
+                        "Warning: skipped malformed article {} of {}: {}",
+                        article.index,
+                        source.name(),
+                        article.error
+                    ),
+                );
             }
         }
+        Ok(articles)
+    }
+
+    /// Warns that `source` contributed nothing to a phase, which usually points to a
+    /// broken conversion step rather than a small corpus.
+    fn warn_if_unusable(&self, source: &InputSource, phase: &str, n_usable: usize) {
+        if n_usable == 0 {
+            report(
+                &self.config,
+                &format!(
+                    "Warning: {} has no usable articles to {}.",
+                    source.name(),
+                    phase
+                ),
+            );
+        }
+    }
+
+    /// Like `tokenizer::tokens_of`, panicking if a protected pattern is no valid regex.
+    pub fn split_abstract_into_words(
+        paper_abstract: String,
+        dedupe: bool,
+        config: &TokenizerConfig,
+    ) -> Vec<String> {
+        tokens_of(&paper_abstract, dedupe, config).unwrap()
+    }
+
+    /// The keyword candidates found so far with the number of abstracts containing them,
+    /// most frequent first.
+    pub fn candidate_frequencies(&self) -> Vec<(String, usize)> {
+        let mut ret: Vec<(String, usize)> = self
+            .keyword_candidates
+            .iter()
+            .map(|(k, count)| (k.clone(), *count))
+            .collect();
+        ret.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         ret
     }
 
-    fn purge_keyword_array(&mut self) {
+    fn write_candidate_frequencies(&self, path: &str) -> std::io::Result<()> {
+        let candidates = self.candidate_frequencies();
+        let mut histogram: Vec<(usize, usize)> = vec![];
+        for (_, count) in candidates.iter().rev() {
+            match histogram.last_mut() {
+                Some((last_count, n)) if last_count == count => *n += 1,
+                _ => histogram.push((*count, 1)),
+            }
+        }
+        let output = CandidateFrequencyOutput {
+            n_files: self.filenames.len(),
+            n_articles: self.n_scanned_articles,
+            candidates,
+            histogram,
+        };
+        let mut file = OutputFile::create(path)?;
+        serde_json::to_writer(&mut file, &output)?;
+        file.finish()?;
+        report(
+            &self.config,
+            &format!("Wrote the keyword candidate frequencies to {}.", path),
+        );
+        Ok(())
+    }
+
+    /// Picks the `lower_cutoff` whose purge leaves the vocabulary size closest to `target`,
+    /// keeping `upper_cutoff` and the allow- and denylists as configured.
+    fn tune_lower_cutoff(&mut self, target: usize) {
         let n_files = self.filenames.len() as f32;
-        let lc = self.lower_cutoff * n_files;
-        let uc = self.upper_cutoff * n_files;
+        let uc = self.config.upper_cutoff * n_files;
+        let allowlist = &self.config.keyword_allowlist;
+        let denylist = &self.config.keyword_denylist;
+        let n_allowed = self
+            .keyword_candidates
+            .keys()
+            .filter(|k| allowlist.contains(*k) && !denylist.contains(*k))
+            .count();
+        let mut counts: Vec<usize> = self
+            .keyword_candidates
+            .iter()
+            .filter(|(k, count)| {
+                !allowlist.contains(*k) && !denylist.contains(*k) && (**count as f32) < uc
+            })
+            .map(|(_, count)| *count)
+            .collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Keeping every count above `threshold` keeps the `n_kept` most frequent candidates,
+        // so the thresholds worth trying are the counts right below each run of ties.
+        let wanted = target.saturating_sub(n_allowed);
+        let mut best = (counts.len().abs_diff(wanted), 0);
+        for n_kept in 0..counts.len() {
+            if n_kept > 0 && counts[n_kept] == counts[n_kept - 1] {
+                continue;
+            }
+            let distance = n_kept.abs_diff(wanted);
+            if distance < best.0 {
+                best = (distance, counts[n_kept]);
+            }
+        }
+        let threshold = best.1;
+        self.tuned_lower_bound = Some(threshold);
+        self.config.lower_cutoff = if n_files > 0.0 {
+            threshold as f32 / n_files
+        } else {
+            0.0
+        };
+        report(
+            &self.config,
+            &format!(
+                "Chose a lower cutoff of {} (more than {} abstracts) for a target vocabulary of {} keywords.",
+                self.config.lower_cutoff, threshold, target
+            ),
+        );
+    }
+
+    /// Keeps the `max_size` keyword candidates found in the most abstracts, breaking ties
+    /// alphabetically, and reports the lower cutoff this amounts to.
+    fn cap_vocabulary(&mut self, max_size: usize) {
+        let n_candidates = self.keyword_candidates.len();
+        if n_candidates <= max_size {
+            return;
+        }
+        let mut ranked: Vec<(&String, usize)> = self
+            .keyword_candidates
+            .iter()
+            .map(|(keyword, count)| (keyword, *count))
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let min_count = ranked.get(max_size.saturating_sub(1)).map_or(0, |k| k.1);
+        let kept: HashSet<String> = ranked
+            .iter()
+            .take(max_size)
+            .map(|(keyword, _)| (*keyword).clone())
+            .collect();
         self.keyword_candidates
-            .retain(|_, &mut count| (count as f32) > lc && (count as f32) < uc)
+            .retain(|keyword, _| kept.contains(keyword));
+        let n_files = self.filenames.len() as f32;
+        let implied_cutoff = if n_files > 0.0 {
+            min_count.saturating_sub(1) as f32 / n_files
+        } else {
+            0.0
+        };
+        report(
+            &self.config,
+            &format!(
+                "Capped the vocabulary at the {} most frequent of {} keywords, found in at least {} abstracts (a lower cutoff of {}).",
+                max_size, n_candidates, min_count, implied_cutoff
+            ),
+        );
+        self.record_vocabulary_size("after the vocabulary cap", self.keyword_candidates.len());
+    }
+
+    /// The abstract counts that `lower_cutoff` and `upper_cutoff` stand for.
+    fn cutoff_bounds(&self) -> (f32, f32) {
+        let n_files = self.filenames.len() as f32;
+        (
+            self.tuned_lower_bound
+                .map_or(self.config.lower_cutoff * n_files, |bound| bound as f32),
+            self.config.upper_cutoff * n_files,
+        )
+    }
+
+    fn purge_keyword_array(&mut self) {
+        let (lc, uc) = self.cutoff_bounds();
+        let tuned_lower_bound = self.tuned_lower_bound;
+        let allowlist = &self.config.keyword_allowlist;
+        let denylist = &self.config.keyword_denylist;
+        self.keyword_candidates.retain(|keyword, &mut count| {
+            let above_lower_bound = match tuned_lower_bound {
+                Some(bound) => count > bound,
+                None => (count as f32) > lc,
+            };
+            !denylist.contains(keyword)
+                && (allowlist.contains(keyword) || (above_lower_bound && (count as f32) < uc))
+        })
     }
 }
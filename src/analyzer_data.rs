@@ -1,13 +1,32 @@
 use histogram::Histogram;
+use rayon::prelude::*;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use sprs::{CsMat, CsVec};
-use std::{collections::HashMap, io::Write};
+use sprs::{CsMat, CsVec, TriMat};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Write,
+};
 
 use crate::{
-    analyzer::{Analyzer, RatedPublication},
+    analyzer::{PublicationRater, RatedPublication},
+    config::{AnalyzerConfig, DiagonalCount, PropagationOperator, RowNormalization},
+    error::AnalyzerError,
+    output::{read_output_file, OutputFile, FORMAT_VERSION},
+    summary::{report, RelationsConnectivity},
+    tokenizer::Tokenizer,
     DEFAULT_HALLMARKS,
 };
 
+pub(crate) const RATING_SUM_TOLERANCE: f32 = 1e-3;
+
+/// Orders ranked entries by descending score, then by ascending key, so that top-N lists
+/// never depend on hash map or thread order. Every top-N helper sorts with it.
+pub fn by_descending_score<K: Ord + ?Sized>(a: (f32, &K), b: (f32, &K)) -> Ordering {
+    b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Hallmark {
     pub title: &'static str,
@@ -20,26 +39,95 @@ pub struct HallmarkRatingOutput {
     pub rating: Vec<f32>,
 }
 
+/// Pre-normalization state of one corpus shard. Relation indices refer to positions in
+/// `keywords`, so shards with different vocabularies can be reconciled on load.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShardOutput {
+    pub keywords: Vec<String>,
+    pub relations: Vec<(usize, usize, f32)>,
+    pub histogram: Vec<u64>,
+    #[serde(default)]
+    pub document_frequencies: Vec<usize>,
+    #[serde(default)]
+    pub n_documents: usize,
+}
+
 #[derive(Serialize, Debug)]
 pub struct FullRunOutput {
+    /// `FORMAT_VERSION` of the crate that wrote the database.
+    pub format_version: u32,
     pub hallmarks: Vec<Hallmark>,
     pub rating_output: Vec<HallmarkRatingOutput>,
 }
 
+/// Statistics of the relations matrix before normalization, as written to
+/// `matrix_stats_output_path`.
+#[derive(Serialize, Debug)]
+pub struct MatrixStatsOutput {
+    pub n_keywords: usize,
+    pub n_nonzero: usize,
+    /// Fraction of nonzero entries.
+    pub density: f32,
+    pub n_documents: usize,
+    pub n_sparse_articles: usize,
+    /// Number of abstracts per bucket of their number of relevant keywords.
+    pub relevant_keyword_histogram: Vec<u64>,
+}
+
 pub struct AnalyzerData {
     keywords_map: HashMap<String, usize>,
     relations: CsMat<f32>,
     keyword_ratings: Vec<CsVec<f32>>,
     n_keywords: usize,
     histogram: Histogram,
+    config: AnalyzerConfig,
+    tokenizer: Tokenizer,
+    unmatched_seed_terms: Vec<Vec<String>>,
+    seeded_keywords: HashSet<usize>,
+    document_frequencies: Vec<usize>,
+    n_documents: usize,
+    /// The diagonal and row sums of the relations matrix as they were before
+    /// `divide_rows_by_diagonal`, empty before it ran.
+    raw_diagonal: Vec<f32>,
+    raw_row_sums: Vec<f32>,
+    /// Articles that `update_with_article_data` left out for `min_relevant_keywords`.
+    n_sparse_articles: usize,
+    /// What `divide_rows_by_diagonal` divided every row by, empty before it ran. The PPMI
+    /// conversion cannot be undone, so its counts are kept aside instead.
+    row_normalizers: Vec<f32>,
+    ppmi_counts: Option<CsMat<f32>>,
+    /// `idf_table` aligned to the keyword indices by `compute_keyword_ratings`.
+    fixed_idf: Vec<f32>,
 }
 
 impl AnalyzerData {
-    pub fn new(n_keywords: usize, keywords: &Vec<String>) -> AnalyzerData {
+    pub fn new(n_keywords: usize, keywords: &[String], config: &AnalyzerConfig) -> AnalyzerData {
         let mut hm = HashMap::new();
         for word in keywords.iter().enumerate() {
             hm.entry(word.1.to_string()).or_insert(word.0);
         }
+        AnalyzerData {
+            n_keywords,
+            keywords_map: hm,
+            relations: CsMat::zero((n_keywords, n_keywords)),
+            keyword_ratings: AnalyzerData::empty_keyword_ratings(n_keywords),
+            histogram: Histogram::new(1, 32).unwrap(),
+            config: config.clone(),
+            tokenizer: Tokenizer::new(&config.tokenizer).expect("invalid protected pattern"),
+            unmatched_seed_terms: vec![],
+            seeded_keywords: HashSet::new(),
+            document_frequencies: vec![0; n_keywords],
+            n_documents: 0,
+            raw_diagonal: vec![],
+            raw_row_sums: vec![],
+            n_sparse_articles: 0,
+            row_normalizers: vec![],
+            ppmi_counts: None,
+            fixed_idf: vec![],
+        }
+    }
+
+    fn empty_keyword_ratings(n_keywords: usize) -> Vec<CsVec<f32>> {
         let mut keyword_ratings = vec![];
         for _i in 0..DEFAULT_HALLMARKS.len() {
             let mut vec = CsVec::empty(n_keywords);
@@ -48,13 +136,168 @@ impl AnalyzerData {
             }
             keyword_ratings.push(vec);
         }
-        AnalyzerData {
-            n_keywords,
-            keywords_map: hm,
-            relations: CsMat::zero((n_keywords, n_keywords)),
-            keyword_ratings,
-            histogram: Histogram::new(1, 32).unwrap(),
+        keyword_ratings
+    }
+
+    /// Adds the raw co-occurrence counts of `other` to this instance, e.g. to combine
+    /// corpus shards processed on different machines. The vocabularies are united and the
+    /// matrices are aligned on the keyword strings, so both instances may use different
+    /// keyword indices.
+    ///
+    /// Merging sums raw counts, so it has to happen before `divide_rows_by_diagonal` on
+    /// both instances. Keyword ratings are reset and must be computed on the merged result.
+    pub fn merge(&mut self, other: AnalyzerData) {
+        let mut other_to_merged: Vec<usize> = vec![0; other.n_keywords];
+        for (keyword, other_index) in other.keywords_map.iter() {
+            let next_index = self.keywords_map.len();
+            let merged_index = *self
+                .keywords_map
+                .entry(keyword.clone())
+                .or_insert(next_index);
+            other_to_merged[*other_index] = merged_index;
+        }
+        let n_keywords = self.keywords_map.len();
+
+        let mut triplets = TriMat::new((n_keywords, n_keywords));
+        for (value, (i, j)) in self.relations.iter() {
+            triplets.add_triplet(i, j, *value);
+        }
+        for (value, (i, j)) in other.relations.iter() {
+            triplets.add_triplet(other_to_merged[i], other_to_merged[j], *value);
+        }
+        self.relations = triplets.to_csr();
+        self.document_frequencies.resize(n_keywords, 0);
+        for (other_index, frequency) in other.document_frequencies.iter().enumerate() {
+            self.document_frequencies[other_to_merged[other_index]] += frequency;
+        }
+        self.n_documents += other.n_documents;
+        self.n_sparse_articles += other.n_sparse_articles;
+        self.n_keywords = n_keywords;
+        self.keyword_ratings = AnalyzerData::empty_keyword_ratings(n_keywords);
+        self.unmatched_seed_terms = vec![];
+        self.seeded_keywords = HashSet::new();
+        self.raw_diagonal = vec![];
+        self.raw_row_sums = vec![];
+        if let Ok(histogram) = self.histogram.wrapping_add(&other.histogram) {
+            self.histogram = histogram;
+        }
+    }
+
+    /// Writes the vocabulary, raw co-occurrence counts and histogram to `path`, compressed
+    /// when it ends in `.gz`. Must be called before `divide_rows_by_diagonal`.
+    pub fn save_shard(&self, path: &str) -> std::io::Result<()> {
+        let shard = ShardOutput {
+            keywords: self.keywords_by_index(),
+            relations: self
+                .relations
+                .iter()
+                .map(|(value, (i, j))| (i, j, *value))
+                .collect(),
+            histogram: self.histogram.as_slice().to_vec(),
+            document_frequencies: self.document_frequencies.clone(),
+            n_documents: self.n_documents,
+        };
+        let mut output = OutputFile::create(path)?;
+        serde_json::to_writer(&mut output, &shard)?;
+        output.finish()
+    }
+
+    /// Replaces the relations matrix, e.g. by one computed elsewhere. Its shape is checked
+    /// against the vocabulary by `compute_keyword_ratings`.
+    pub fn set_relations(&mut self, relations: CsMat<f32>) {
+        self.relations = relations;
+    }
+
+    /// Reads a shard written by `save_shard`. Combine several shards with `merge`.
+    pub fn load_shard(path: &str, config: &AnalyzerConfig) -> std::io::Result<AnalyzerData> {
+        let file_contents = read_output_file(path)?;
+        let shard: ShardOutput = serde_json::from_str(&file_contents)?;
+        let mut data = AnalyzerData::new(shard.keywords.len(), &shard.keywords, config);
+        let mut triplets = TriMat::new((data.n_keywords, data.n_keywords));
+        for (i, j, value) in shard.relations {
+            triplets.add_triplet(i, j, value);
+        }
+        data.relations = triplets.to_csr();
+        if shard.document_frequencies.len() == data.n_keywords {
+            data.document_frequencies = shard.document_frequencies;
+            data.n_documents = shard.n_documents;
         }
+        data.histogram = Histogram::from_buckets(1, 32, shard.histogram).map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e))
+        })?;
+        Ok(data)
+    }
+
+    /// Stores the number of abstracts each keyword occurs in, as counted during the
+    /// candidate scan over `n_documents` abstracts.
+    pub fn set_document_frequencies(
+        &mut self,
+        frequencies: &HashMap<String, usize>,
+        n_documents: usize,
+    ) {
+        for (keyword, index) in self.keywords_map.iter() {
+            self.document_frequencies[*index] = *frequencies.get(keyword).unwrap_or(&0);
+        }
+        self.n_documents = n_documents;
+    }
+
+    /// Smoothed inverse document frequency `ln(N / df) + 1`. Keywords without a known
+    /// document frequency get a neutral weight of 1. With an `idf_table` the weight comes
+    /// from the table once the keyword ratings are computed.
+    pub fn inverse_document_frequency(&self, keyword_index: usize) -> f32 {
+        if let Some(idf) = self.fixed_idf.get(keyword_index) {
+            return *idf;
+        }
+        let frequency = self.document_frequencies[keyword_index];
+        if frequency == 0 || self.n_documents == 0 {
+            return 1.0;
+        }
+        (self.n_documents as f32 / frequency as f32).ln() + 1.0
+    }
+
+    /// The number of abstracts each keyword occurs in, as stored by
+    /// `set_document_frequencies`. Unlike the diagonal of the relations matrix it survives
+    /// `divide_rows_by_diagonal` and does not depend on `cooccurrence_weighting`. Divide by
+    /// `n_documents` for the fraction of abstracts supporting a keyword.
+    pub fn keyword_supports(&self) -> HashMap<String, usize> {
+        self.keywords_map
+            .iter()
+            .map(|(keyword, index)| (keyword.clone(), self.document_frequencies[*index]))
+            .collect()
+    }
+
+    /// The diagonal entry of `keyword` in the relations matrix before
+    /// `divide_rows_by_diagonal`. With the default binary `cooccurrence_weighting` this is
+    /// the number of abstracts containing the keyword in the matrix build. `None` before
+    /// normalization or if the keyword is not in the vocabulary.
+    pub fn raw_diagonal(&self, keyword: &str) -> Option<f32> {
+        let index = self.keywords_map.get(keyword)?;
+        self.raw_diagonal.get(*index).copied()
+    }
+
+    /// The sum of the row of `keyword` in the relations matrix before
+    /// `divide_rows_by_diagonal`, `None` like `raw_diagonal`.
+    pub fn raw_row_sum(&self, keyword: &str) -> Option<f32> {
+        let index = self.keywords_map.get(keyword)?;
+        self.raw_row_sums.get(*index).copied()
+    }
+
+    /// The number of abstracts of the candidate scan, 0 if unknown.
+    pub fn n_documents(&self) -> usize {
+        self.n_documents
+    }
+
+    pub fn n_sparse_articles(&self) -> usize {
+        self.n_sparse_articles
+    }
+
+    pub fn n_keywords(&self) -> usize {
+        self.n_keywords
+    }
+
+    /// The row and column of `keyword` in the relations matrix.
+    pub fn keyword_index(&self, keyword: &str) -> Option<usize> {
+        self.keywords_map.get(keyword).copied()
     }
 
     pub fn print(&self) {
@@ -71,96 +314,651 @@ impl AnalyzerData {
             percentage
         );
         let buckets = self.histogram.as_slice();
-        for b in 0..buckets.len() {
-            if buckets[b] > 0 {
-                println!("In bucket {}: {}", b, buckets[b]);
+        for (b, count) in buckets.iter().enumerate() {
+            if *count > 0 {
+                println!("In bucket {}: {}", b, count);
             }
         }
     }
 
-    pub fn update_with_article_data(&mut self, words: &Vec<String>) {
-        let mut present_keywords = vec![];
-        for word in words.iter() {
-            if self.keywords_map.contains_key(word) {
-                present_keywords.push(word.clone());
+    pub fn matrix_stats(&self) -> MatrixStatsOutput {
+        let n_matrix_entries = self.n_keywords * self.n_keywords;
+        MatrixStatsOutput {
+            n_keywords: self.n_keywords,
+            n_nonzero: self.relations.nnz(),
+            density: if n_matrix_entries > 0 {
+                self.relations.nnz() as f32 / n_matrix_entries as f32
+            } else {
+                0.0
+            },
+            n_documents: self.n_documents,
+            n_sparse_articles: self.n_sparse_articles,
+            relevant_keyword_histogram: self.histogram.as_slice().to_vec(),
+        }
+    }
+
+    pub fn write_matrix_stats(&self, path: &str) -> std::io::Result<()> {
+        let mut output = OutputFile::create(path)?;
+        serde_json::to_writer_pretty(&mut output, &self.matrix_stats())?;
+        output.finish()
+    }
+
+    /// Writes the vocabulary as one keyword per line, sorted, like the keyword lists read by
+    /// `loaders::load_keyword_list`.
+    pub fn write_vocabulary(&self, path: &str) -> std::io::Result<()> {
+        let mut keywords: Vec<&String> = self.keywords_map.keys().collect();
+        keywords.sort();
+        let mut output = OutputFile::create(path)?;
+        for keyword in keywords {
+            writeln!(output, "{}", keyword)?;
+        }
+        output.finish()
+    }
+
+    /// Counts the co-occurrences of the vocabulary keywords among the tokens of one article,
+    /// given with their number of occurrences. Each pair adds both keywords'
+    /// `cooccurrence_weighting` combined by `cooccurrence_pairing`, which is 1 in the default
+    /// binary mode. The diagonal entry of
+    /// a keyword adds what `diagonal_count` asks for. Returns how many of the tokens are
+    /// vocabulary keywords, or 0 if the article has fewer than `min_relevant_keywords` and
+    /// adds nothing.
+    pub fn update_with_article_data(&mut self, words: &[(String, usize)]) -> usize {
+        let mut indices: Vec<usize> = vec![];
+        let mut weights: Vec<f32> = vec![];
+        let mut counts: Vec<usize> = vec![];
+        for (word, count) in words.iter() {
+            if let Some(index) = self.keywords_map.get(word) {
+                indices.push(*index);
+                weights.push(self.config.cooccurrence_weighting.weight(*count));
+                counts.push(*count);
             }
         }
-        let indices: Vec<usize> = present_keywords
-            .iter()
-            .map(|w| *self.keywords_map.get(w).unwrap())
-            .collect();
         let n_relevant_words = indices.len();
         let _ = self.histogram.increment(n_relevant_words as u64);
+        if n_relevant_words < self.config.min_relevant_keywords {
+            self.n_sparse_articles += 1;
+            return 0;
+        }
+        let first_partner = if self.config.exclude_self_pairs { 1 } else { 0 };
         for i in 0..n_relevant_words {
             let ind_i = indices[i];
-            for j in i..n_relevant_words {
-                let ind_j = indices[j];
+            for (j, &ind_j) in indices.iter().enumerate().skip(i + first_partner) {
+                let increment =
+                    cooccurrence_increment(&self.config, i == j, counts[i], weights[i], weights[j]);
                 let current = self.relations.get(ind_i, ind_j).unwrap_or(&0.0).to_owned();
-                let next = current + 1.0;
+                let next = current + increment;
                 self.relations.insert(ind_i, ind_j, next);
                 self.relations.insert(ind_j, ind_i, next);
             }
         }
+        n_relevant_words
+    }
+
+    /// Adds co-occurrences recorded elsewhere, e.g. by a single-pass scan, to the relations
+    /// matrix. Every entry `(i, j, value)` is added to both `(i, j)` and `(j, i)`.
+    /// `n_relevant_words` holds the number of keywords of every recorded article.
+    pub fn add_recorded_cooccurrences(
+        &mut self,
+        entries: &[(usize, usize, f32)],
+        n_relevant_words: &[usize],
+    ) {
+        let mut triplets = TriMat::new(self.relations.shape());
+        for (value, (i, j)) in self.relations.iter() {
+            triplets.add_triplet(i, j, *value);
+        }
+        for &(i, j, value) in entries.iter() {
+            triplets.add_triplet(i, j, value);
+            if i != j {
+                triplets.add_triplet(j, i, value);
+            }
+        }
+        self.relations = triplets.to_csr();
+        for n in n_relevant_words.iter() {
+            let _ = self.histogram.increment(*n as u64);
+        }
     }
 
+    /// The entry of the relations matrix for the keyword pair, `None` if either keyword is
+    /// not in the vocabulary.
+    pub fn co_occurrence(&self, a: &str, b: &str) -> Option<f32> {
+        let i = self.keywords_map.get(a)?;
+        let j = self.keywords_map.get(b)?;
+        Some(*self.relations.get(*i, *j).unwrap_or(&0.0))
+    }
+
+    /// The vocabulary ordered by keyword index.
+    fn keywords_by_index(&self) -> Vec<String> {
+        let mut keywords = vec![String::new(); self.n_keywords];
+        for (keyword, index) in self.keywords_map.iter() {
+            keywords[*index] = keyword.clone();
+        }
+        keywords
+    }
+
+    /// Writes the vocabulary as one `index<TAB>keyword` line per keyword, sorted by index.
+    /// This is the companion file of every export that refers to keywords by their row or
+    /// column in the relations matrix.
+    pub fn write_keyword_index(&self, path: &str) -> std::io::Result<()> {
+        let mut output = OutputFile::create(path)?;
+        self.write_keyword_index_to(&mut output)?;
+        output.finish()
+    }
+
+    pub fn write_keyword_index_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for (index, keyword) in self.keywords_by_index().iter().enumerate() {
+            writeln!(writer, "{}\t{}", index, keyword)?;
+        }
+        writer.flush()
+    }
+
+    /// The nonzero entries of the relations matrix as `(row, column, value)`. The diagonal
+    /// only counts how often a keyword occurs at all and would dominate any ranking of
+    /// pairs, so exports of keyword pairs should pass `include_diagonal = false`.
+    pub fn relation_entries(
+        &self,
+        include_diagonal: bool,
+    ) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        self.relations
+            .iter()
+            .filter(move |(value, (i, j))| **value != 0.0 && (include_diagonal || i != j))
+            .map(|(value, (i, j))| (i, j, *value))
+    }
+
+    /// Connected components, isolated keywords and the degree distribution of the
+    /// co-occurrence graph of the relations matrix.
+    pub fn connectivity(&self) -> RelationsConnectivity {
+        let n_nodes = self.relations.rows();
+        let mut parents: Vec<usize> = (0..n_nodes).collect();
+        let mut degrees = vec![0; n_nodes];
+        for (i, j, _) in self.relation_entries(false) {
+            degrees[i] += 1;
+            let (a, b) = (
+                component_root(&mut parents, i),
+                component_root(&mut parents, j),
+            );
+            if a != b {
+                parents[a] = b;
+            }
+        }
+        let n_components = (0..n_nodes)
+            .filter(|n| component_root(&mut parents, *n) == *n)
+            .count();
+        let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+        for degree in degrees.iter() {
+            *histogram.entry(*degree).or_insert(0) += 1;
+        }
+        RelationsConnectivity {
+            n_components,
+            n_isolated: degrees.iter().filter(|d| **d == 0).count(),
+            degree_histogram: histogram.into_iter().collect(),
+        }
+    }
+
+    /// The `n` keyword pairs with the largest entries of the relations matrix, largest
+    /// first. After `divide_rows_by_diagonal` an entry is the fraction of abstracts of the
+    /// first keyword that also contain the second.
+    pub fn top_pairs(&self, n: usize, include_diagonal: bool) -> Vec<(String, String, f32)> {
+        let keywords = self.keywords_by_index();
+        let mut pairs: Vec<(String, String, f32)> = self
+            .relation_entries(include_diagonal)
+            .map(|(i, j, value)| (keywords[i].clone(), keywords[j].clone(), value))
+            .collect();
+        pairs.sort_by(|a, b| by_descending_score((a.2, &(&a.0, &a.1)), (b.2, &(&b.0, &b.1))));
+        pairs.truncate(n);
+        pairs
+    }
+
+    /// The `n` keywords co-occurring most with `keyword`, strongest first, from its row of
+    /// the relations matrix without the diagonal. With `raw_counts` the co-occurrence counts
+    /// are ranked even after `divide_rows_by_diagonal`, otherwise the current entries. Empty
+    /// for a keyword outside the vocabulary.
+    pub fn neighbors(&self, keyword: &str, n: usize, raw_counts: bool) -> Vec<(String, f32)> {
+        let Some(&i) = self.keywords_map.get(keyword) else {
+            return vec![];
+        };
+        let (matrix, factor) = if raw_counts {
+            self.raw_counts_of_row(i)
+        } else {
+            (&self.relations, 1.0)
+        };
+        let Some(row) = matrix.outer_view(i) else {
+            return vec![];
+        };
+        let keywords = self.keywords_by_index();
+        let mut ret: Vec<(String, f32)> = row
+            .iter()
+            .filter(|(j, value)| *j != i && **value != 0.0)
+            .map(|(j, value)| (keywords[j].clone(), value * factor))
+            .collect();
+        ret.sort_by(|a, b| by_descending_score((a.1, &a.0), (b.1, &b.0)));
+        ret.truncate(n);
+        ret
+    }
+
+    /// The matrix holding the co-occurrence counts of row `i` and the factor to scale its
+    /// entries by, before as well as after `divide_rows_by_diagonal`.
+    fn raw_counts_of_row(&self, i: usize) -> (&CsMat<f32>, f32) {
+        match &self.ppmi_counts {
+            Some(counts) => (counts, 1.0),
+            None => (
+                &self.relations,
+                self.row_normalizers.get(i).copied().unwrap_or(1.0),
+            ),
+        }
+    }
+
+    /// The Jaccard index of the abstracts containing `a` and those containing `b`, `None`
+    /// if one of them is outside the vocabulary and 0 without document frequencies. The
+    /// article sets are not stored, so the intersection is taken from the raw co-occurrence
+    /// count and the set sizes from `set_document_frequencies`. This is exact for the
+    /// default binary `cooccurrence_weighting` if the relations matrix was built from the
+    /// same abstracts as the candidate scan; with other weightings, or with abstracts left
+    /// out for `min_relevant_keywords`, it is an approximation.
+    pub fn jaccard(&self, a: &str, b: &str) -> Option<f32> {
+        let i = *self.keywords_map.get(a)?;
+        let j = *self.keywords_map.get(b)?;
+        let (frequency_a, frequency_b) = (
+            self.document_frequencies[i] as f32,
+            self.document_frequencies[j] as f32,
+        );
+        let (matrix, factor) = self.raw_counts_of_row(i);
+        let intersection = (matrix.get(i, j).unwrap_or(&0.0) * factor)
+            .min(frequency_a)
+            .min(frequency_b);
+        let union = frequency_a + frequency_b - intersection;
+        if union <= 0.0 {
+            return Some(0.0);
+        }
+        Some(intersection / union)
+    }
+
+    /// Checks that the raw co-occurrence counts are symmetric, as they must be before
+    /// `divide_rows_by_diagonal`. Reports the first asymmetric pair found.
+    pub fn check_symmetry(&self) -> Result<(), AnalyzerError> {
+        let keywords = self.keywords_by_index();
+        for (i, j, value) in self.relation_entries(true) {
+            let transposed = *self.relations.get(j, i).unwrap_or(&0.0);
+            if value != transposed {
+                return Err(AnalyzerError::AsymmetricRelations {
+                    keyword: keywords[i].clone(),
+                    partner: keywords[j].clone(),
+                    value,
+                    transposed,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Turns the co-occurrence counts into conditional frequencies by dividing each row by
+    /// the number of abstracts containing its keyword. That number is the diagonal, unless
+    /// `exclude_self_pairs` is set: then the diagonal stays empty and the document
+    /// frequencies from the candidate scan are used instead. With
+    /// `RowNormalization::LogDiagonal` the rows are divided by `ln(1 + count)` instead.
+    /// Keywords without a known count keep their raw row. `RowNormalization::Ppmi` replaces
+    /// the counts by `ppmi` instead of dividing rows.
     pub fn divide_rows_by_diagonal(&mut self) {
         let diag = self.relations.diag();
-        for i in 0..self.n_keywords {
+        self.raw_diagonal = (0..self.n_keywords)
+            .map(|i| diag.get(i).copied().unwrap_or(0.0))
+            .collect();
+        self.raw_row_sums = self
+            .relations
+            .outer_iterator()
+            .map(|row| row.data().iter().sum())
+            .collect();
+        if self.config.row_normalization == RowNormalization::Ppmi {
+            self.ppmi_counts = Some(self.relations.clone());
+            self.convert_to_ppmi();
+            return;
+        }
+        let counts: Vec<Option<f32>> = if self.config.exclude_self_pairs {
+            self.document_frequencies
+                .iter()
+                .map(|df| if *df > 0 { Some(*df as f32) } else { None })
+                .collect()
+        } else {
+            (0..self.n_keywords).map(|i| diag.get(i).copied()).collect()
+        };
+        let normalizers: Vec<f32> = counts
+            .iter()
+            .map(|count| match (count, self.config.row_normalization) {
+                (None, _) => 1.0,
+                (Some(count), RowNormalization::LogDiagonal) => count.ln_1p(),
+                (Some(count), _) => *count,
+            })
+            .collect();
+        for (i, normalizer) in normalizers.iter().enumerate() {
             for j in 0..self.n_keywords {
-                let opt_val = self.relations.get_mut(i, j);
-                match opt_val {
-                    Some(val) => {
-                        *val = *val / diag.get(i).unwrap_or(&1.0);
-                    }
-                    None => {}
+                if let Some(val) = self.relations.get_mut(i, j) {
+                    *val /= normalizer;
+                }
+            }
+        }
+        self.row_normalizers = normalizers;
+    }
+
+    /// Undoes `divide_rows_by_diagonal`, if it ran, so the relations matrix holds counts
+    /// again.
+    fn restore_raw_relations(&mut self) {
+        if let Some(counts) = self.ppmi_counts.take() {
+            self.relations = counts;
+        }
+        let normalizers = std::mem::take(&mut self.row_normalizers);
+        if !normalizers.is_empty() {
+            for (i, mut row) in self.relations.outer_iterator_mut().enumerate() {
+                for (_, value) in row.iter_mut() {
+                    *value *= normalizers[i];
+                }
+            }
+        }
+    }
+
+    /// Counts further tokenized articles into a model, e.g. new publications of an existing
+    /// corpus, like `update_with_article_data` does during training. The document
+    /// frequencies are updated as well. Tokens outside the vocabulary are ignored unless
+    /// `vocabulary_growth` lets them join it. A normalized relations matrix holds counts
+    /// again afterwards, and the keyword ratings are stale until `refinalize`. Returns how
+    /// many articles contained keywords.
+    pub fn add_articles(&mut self, articles: &[Vec<(String, usize)>]) -> usize {
+        self.restore_raw_relations();
+        if let Some(min_abstracts) = self.config.vocabulary_growth {
+            let n_added = self.grow_vocabulary(articles, min_abstracts);
+            report(
+                &self.config,
+                &format!("{} keywords joined the vocabulary.", n_added),
+            );
+        }
+        let mut n_counted = 0;
+        for words in articles.iter() {
+            if self.update_with_article_data(words) > 0 {
+                n_counted += 1;
+            }
+            for (word, _) in words.iter() {
+                if let Some(index) = self.keywords_map.get(word) {
+                    self.document_frequencies[*index] += 1;
                 }
             }
         }
+        self.n_documents += articles.len();
+        n_counted
+    }
+
+    /// Adds the terms outside the vocabulary that occur in at least `min_abstracts` of
+    /// `articles`, growing the relations matrix and the keyword ratings with empty entries.
+    /// Co-occurrences of a new keyword with articles counted before are unknown and stay
+    /// missing. Returns the number of new keywords.
+    fn grow_vocabulary(
+        &mut self,
+        articles: &[Vec<(String, usize)>],
+        min_abstracts: usize,
+    ) -> usize {
+        let mut frequencies: HashMap<&str, usize> = HashMap::new();
+        for (word, _) in articles.iter().flatten() {
+            if !self.keywords_map.contains_key(word) && !self.config.keyword_denylist.contains(word)
+            {
+                *frequencies.entry(word).or_insert(0) += 1;
+            }
+        }
+        let mut new_keywords: Vec<&str> = frequencies
+            .into_iter()
+            .filter(|(_, frequency)| *frequency >= min_abstracts)
+            .map(|(word, _)| word)
+            .collect();
+        if new_keywords.is_empty() {
+            return 0;
+        }
+        new_keywords.sort_unstable();
+        let n_previous = self.n_keywords;
+        let n_keywords = n_previous + new_keywords.len();
+        for (offset, keyword) in new_keywords.iter().enumerate() {
+            self.keywords_map
+                .insert(keyword.to_string(), n_previous + offset);
+        }
+
+        let mut triplets = TriMat::new((n_keywords, n_keywords));
+        for (value, (i, j)) in self.relations.iter() {
+            triplets.add_triplet(i, j, *value);
+        }
+        self.relations = triplets.to_csr();
+        for ratings in self.keyword_ratings.iter_mut() {
+            let mut grown = CsVec::empty(n_keywords);
+            for (i, value) in ratings.iter() {
+                grown.append(i, *value);
+            }
+            for i in n_previous..n_keywords {
+                grown.append(i, 0.0);
+            }
+            *ratings = grown;
+        }
+        self.document_frequencies.resize(n_keywords, 0);
+        self.n_keywords = n_keywords;
+        n_keywords - n_previous
+    }
+
+    /// Normalizes the relations matrix and computes the keyword ratings from scratch, after
+    /// `add_articles` or on a model whose ratings were computed before.
+    pub fn refinalize(&mut self) -> Result<(), AnalyzerError> {
+        self.restore_raw_relations();
+        self.divide_rows_by_diagonal();
+        self.keyword_ratings = AnalyzerData::empty_keyword_ratings(self.n_keywords);
+        self.compute_keyword_ratings()
+    }
+
+    /// Replaces every co-occurrence count by the positive pointwise mutual information of
+    /// its pair, see `RowNormalization::Ppmi`. Entries of keywords without a document
+    /// frequency, e.g. from shards saved without one, become 0.
+    fn convert_to_ppmi(&mut self) {
+        let n_documents = self.n_documents as f32;
+        let frequencies = &self.document_frequencies;
+        for (i, mut row) in self.relations.outer_iterator_mut().enumerate() {
+            for (j, value) in row.iter_mut() {
+                let denominator = (frequencies[i] * frequencies[j]) as f32;
+                *value = if denominator > 0.0 && *value > 0.0 {
+                    (n_documents * *value / denominator).ln().max(0.0)
+                } else {
+                    0.0
+                };
+            }
+        }
     }
 
-    pub fn compute_keyword_ratings(&mut self) {
+    /// Seeds the hallmark ratings and propagates them through the relations matrix. Fails if
+    /// the matrix does not match the vocabulary.
+    pub fn compute_keyword_ratings(&mut self) -> Result<(), AnalyzerError> {
+        self.align_fixed_idf();
+        self.unmatched_seed_terms = vec![];
+        self.seeded_keywords = HashSet::new();
         for hallmark in DEFAULT_HALLMARKS.iter().enumerate() {
-            let terms =
-                Analyzer::split_abstract_into_words(hallmark.1.description.to_string(), true);
+            let explicit_seeds = self.config.seed_keywords.get(hallmark.1.title);
+            let mut terms = match explicit_seeds {
+                Some(seeds) => seeds
+                    .iter()
+                    .map(|s| self.tokenizer.canonical(s).to_string())
+                    .collect(),
+                None => self.tokenizer.unique_tokens(hallmark.1.description),
+            };
+            for synonym in self.synonym_seeds(hallmark.1.description, explicit_seeds) {
+                let synonym = self.tokenizer.canonical(&synonym).to_string();
+                if !terms.contains(&synonym) {
+                    terms.push(synonym);
+                }
+            }
+            let mut unmatched = vec![];
             for t in terms {
-                if self.keywords_map.contains_key(&t) {
-                    let keyword_index = *self.keywords_map.get(&t).unwrap();
-                    let previous = self.keyword_ratings[hallmark.0][keyword_index];
-                    self.keyword_ratings[hallmark.0][keyword_index] = previous + 1.0;
+                if let Some(keyword_index) = self.keywords_map.get(&t) {
+                    let previous = self.keyword_ratings[hallmark.0][*keyword_index];
+                    self.keyword_ratings[hallmark.0][*keyword_index] = previous + 1.0;
+                    self.seeded_keywords.insert(*keyword_index);
+                } else {
+                    unmatched.push(t);
+                }
+            }
+            if explicit_seeds.is_some() {
+                for seed in unmatched.iter() {
+                    report(
+                        &self.config,
+                        &format!(
+                            "Warning: seed keyword '{}' of hallmark '{}' is not in the vocabulary.",
+                            seed, hallmark.1.title
+                        ),
+                    );
                 }
             }
+            self.unmatched_seed_terms.push(unmatched);
         }
         let n_unrated_keywords = self.normalize_keyword_rating();
-        println!(
-            "{} unrated keywords after initialization.",
-            n_unrated_keywords
+        if let Some(path) = &self.config.seed_rating_output_path {
+            self.write_rating_database(path).unwrap();
+            report(
+                &self.config,
+                &format!("Wrote the seeded keyword ratings to {}.", path),
+            );
+        }
+        report(
+            &self.config,
+            &format!(
+                "{} keywords seeded directly, {} unrated keywords after initialization.",
+                self.seeded_keywords.len(),
+                n_unrated_keywords
+            ),
         );
         let n_max_update_steps = 1;
         for i in 0..n_max_update_steps {
-            self.update_rating();
+            self.update_rating()?;
             let unrated_words = self.normalize_keyword_rating();
-            println!("{} unrated keywords left in cycle {}", unrated_words, i);
+            report(
+                &self.config,
+                &format!("{} unrated keywords left in cycle {}", unrated_words, i),
+            );
         }
+        Ok(())
     }
 
-    fn update_rating(&mut self) {
-        for hallmark_index in 0..DEFAULT_HALLMARKS.len() {
-            let mat: &CsMat<f32> = &self.relations;
-            let vec: &CsVec<f32> = &self.keyword_ratings[hallmark_index];
-            let new_rating = mat * vec;
-            self.keyword_ratings[hallmark_index] = new_rating;
+    /// Looks up every keyword in `idf_table`, after the vocabulary is final.
+    fn align_fixed_idf(&mut self) {
+        let Some(table) = &self.config.idf_table else {
+            return;
+        };
+        self.fixed_idf = vec![self.config.default_idf; self.n_keywords];
+        for (keyword, index) in self.keywords_map.iter() {
+            if let Some(idf) = table.get(keyword) {
+                self.fixed_idf[*index] = *idf;
+            }
         }
     }
 
-    pub fn write_rating_output(&self) {
+    /// Keywords of all `seed_synonyms` phrases found in the lowercased `description`, or
+    /// listed verbatim in the explicit seeds of the hallmark if it has any.
+    fn synonym_seeds(
+        &self,
+        description: &str,
+        explicit_seeds: Option<&Vec<String>>,
+    ) -> Vec<String> {
+        let description = description.to_lowercase();
+        let mut ret = vec![];
+        for (phrase, keywords) in self.config.seed_synonyms.iter() {
+            let found = match explicit_seeds {
+                Some(seeds) => seeds.contains(phrase),
+                None => description.contains(phrase.as_str()),
+            };
+            if found {
+                ret.extend(keywords.iter().cloned());
+            }
+        }
+        ret
+    }
+
+    /// Whether `keyword` was seeded directly by a hallmark during the last
+    /// `compute_keyword_ratings`, rather than only rated through propagation.
+    pub fn is_seeded(&self, keyword: &str) -> bool {
+        self.keywords_map
+            .get(keyword)
+            .is_some_and(|index| self.seeded_keywords.contains(index))
+    }
+
+    /// The keywords seeded directly by any hallmark, sorted.
+    pub fn seeded_keywords(&self) -> Vec<String> {
+        let mut ret: Vec<String> = self
+            .keywords_map
+            .iter()
+            .filter(|(_, index)| self.seeded_keywords.contains(*index))
+            .map(|(keyword, _)| keyword.clone())
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    /// Seed terms of each hallmark (indexed like `DEFAULT_HALLMARKS`) that were not found in
+    /// the vocabulary during the last `compute_keyword_ratings`.
+    pub fn unmatched_seed_terms(&self) -> &[Vec<String>] {
+        &self.unmatched_seed_terms
+    }
+
+    fn update_rating(&mut self) -> Result<(), AnalyzerError> {
+        let without_self_loops;
+        let mat: &CsMat<f32> = if self.config.exclude_self_loops {
+            let mut triplets = TriMat::new(self.relations.shape());
+            for (value, (i, j)) in self.relations.iter() {
+                if i != j {
+                    triplets.add_triplet(i, j, *value);
+                }
+            }
+            without_self_loops = triplets.to_csr();
+            &without_self_loops
+        } else {
+            &self.relations
+        };
+        let operator;
+        let mat: &CsMat<f32> = match self.config.propagation {
+            PropagationOperator::Rows => mat,
+            _ if mat.rows() != mat.cols() => mat,
+            PropagationOperator::Transposed => {
+                operator = mat.transpose_view().to_csr();
+                &operator
+            }
+            PropagationOperator::Symmetrized => {
+                let transposed = mat.transpose_view().to_csr();
+                operator = (mat + &transposed).map(|value| value / 2.0);
+                &operator
+            }
+        };
+        for (hallmark, rating) in DEFAULT_HALLMARKS
+            .iter()
+            .zip(self.keyword_ratings.iter_mut())
+        {
+            if mat.rows() != mat.cols() || mat.cols() != rating.dim() {
+                return Err(AnalyzerError::DimensionMismatch {
+                    hallmark: hallmark.title,
+                    matrix_shape: mat.shape(),
+                    vector_dim: rating.dim(),
+                });
+            }
+            *rating = mat * &*rating;
+        }
+        Ok(())
+    }
+
+    /// The keyword rating database as written by `write_rating_output`. With
+    /// `min_output_rating` set, keywords whose highest hallmark rating is below it, as well
+    /// as unrated keywords, are left out. With `output_keyword_pattern` set, only matching
+    /// keywords are kept.
+    pub fn rating_output(&self) -> FullRunOutput {
+        let pattern = self
+            .config
+            .output_keyword_pattern
+            .as_ref()
+            .map(|p| Regex::new(p).expect("invalid output keyword pattern"));
         let mut rating_output: Vec<HallmarkRatingOutput> = vec![];
         for w in self.keywords_map.clone() {
-            let mut rating: Vec<f32> = vec![];
-            for i in 0..DEFAULT_HALLMARKS.len() {
-                if self.is_rating_non_zero(w.1, i) {
-                    rating.push(self.keyword_ratings[i][w.1]);
-                } else {
-                    rating.push(0.0);
+            if pattern.as_ref().is_some_and(|p| !p.is_match(&w.0)) {
+                continue;
+            }
+            let rating = self.rating_of(w.1);
+            if let Some(min_rating) = self.config.min_output_rating {
+                let max_rating = rating.iter().copied().fold(0.0, f32::max);
+                if max_rating <= 0.0 || max_rating < min_rating {
+                    continue;
                 }
             }
             rating_output.push(HallmarkRatingOutput {
@@ -168,26 +966,228 @@ impl AnalyzerData {
                 rating,
             });
         }
-        let full_output: FullRunOutput = FullRunOutput {
+        rating_output.sort_by(|a, b| a.keyword.cmp(&b.keyword));
+        FullRunOutput {
+            format_version: FORMAT_VERSION,
             hallmarks: DEFAULT_HALLMARKS.to_vec(),
             rating_output,
-        };
-        let output_json = serde_json::to_string_pretty(&full_output).unwrap();
-        let mut file = std::fs::File::create("rating_database.json".to_string()).unwrap();
-        file.write_all(output_json.as_bytes()).unwrap();
+        }
+    }
+
+    /// Rated keywords whose hallmark components do not sum to 1, with their sum. Unrated
+    /// keywords are not violations.
+    pub fn rating_normalization_violations(&self) -> Vec<(String, f32)> {
+        let mut violations = vec![];
+        for (keyword, index) in self.keywords_map.iter() {
+            let sum: f32 = self.rating_of(*index).iter().sum();
+            if sum != 0.0 && (sum - 1.0).abs() > RATING_SUM_TOLERANCE {
+                violations.push((keyword.clone(), sum));
+            }
+        }
+        violations.sort_by(|a, b| a.0.cmp(&b.0));
+        violations
+    }
+
+    /// Every NaN or infinite hallmark rating of a keyword as `(keyword, hallmark index,
+    /// value)`, sorted by keyword and hallmark. Normalization and propagation divide by sums
+    /// that may be 0, so this catches whatever slipped through.
+    pub fn non_finite_ratings(&self) -> Vec<(String, usize, f32)> {
+        let keywords = self.keywords_by_index();
+        let mut ret = vec![];
+        for (hallmark, rating) in self.keyword_ratings.iter().enumerate() {
+            for (index, value) in rating.iter() {
+                if !value.is_finite() {
+                    ret.push((keywords[index].clone(), hallmark, *value));
+                }
+            }
+        }
+        ret.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        ret
+    }
+
+    fn warn_about_rating_violations(&self) {
+        let violations = self.rating_normalization_violations();
+        if violations.is_empty() {
+            return;
+        }
+        report(
+            &self.config,
+            &format!(
+                "Warning: {} keyword ratings do not sum to 1.",
+                violations.len()
+            ),
+        );
+        for (keyword, sum) in violations.iter().take(10) {
+            report(&self.config, &format!("  {}: {}", keyword, sum));
+        }
+    }
+
+    pub fn write_rating_output(&self) {
+        if self.config.validate_ratings {
+            self.warn_about_rating_violations();
+        }
+        self.write_rating_database(&self.config.rating_output_path)
+            .unwrap();
+    }
+
+    fn write_rating_database(&self, path: &str) -> std::io::Result<()> {
+        let mut output = OutputFile::create(path)?;
+        self.write_rating_output_to(&mut output)?;
+        output.finish()
+    }
+
+    /// Writes the rating database as pretty-printed JSON to `writer`.
+    pub fn write_rating_output_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let full_output: FullRunOutput = self.rating_output();
+        serde_json::to_writer_pretty(&mut writer, &full_output)?;
+        writer.flush()
+    }
+
+    /// Per-keyword specificity: the entropy of each rated keyword's hallmark distribution,
+    /// sorted ascending so the most diagnostic keywords come first. Unrated keywords are
+    /// skipped.
+    pub fn keyword_entropies(&self) -> Vec<(String, f32)> {
+        let mut entropies: Vec<(String, f32)> = vec![];
+        for (keyword, index) in self.keywords_map.iter() {
+            let rating = self.rating_of(*index);
+            if rating.iter().all(|r| *r <= 0.0) {
+                continue;
+            }
+            let entropy: f32 = rating
+                .iter()
+                .filter(|p| **p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum();
+            entropies.push((keyword.clone(), entropy));
+        }
+        entropies.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        entropies
+    }
+
+    /// Cosine similarity of every pair of hallmarks' keyword rating vectors, indexed like
+    /// `DEFAULT_HALLMARKS`. Values near 1 point at hallmarks that rate the same keywords and
+    /// may overlap in the taxonomy. Hallmarks without rated keywords correlate with nothing.
+    pub fn hallmark_correlations(&self) -> Vec<Vec<f32>> {
+        let norms: Vec<f32> = self.keyword_ratings.iter().map(|r| r.l2_norm()).collect();
+        let mut correlations = vec![vec![0.0; DEFAULT_HALLMARKS.len()]; DEFAULT_HALLMARKS.len()];
+        for (i, row) in correlations.iter_mut().enumerate() {
+            for (j, correlation) in row.iter_mut().enumerate() {
+                if norms[i] > 0.0 && norms[j] > 0.0 {
+                    let dot = self.keyword_ratings[i].dot(&self.keyword_ratings[j]);
+                    *correlation = dot / (norms[i] * norms[j]);
+                }
+            }
+        }
+        correlations
+    }
+
+    pub fn print_hallmark_correlations(&self) {
+        println!("Hallmark correlations in keyword space:");
+        for (i, hallmark) in DEFAULT_HALLMARKS.iter().enumerate() {
+            println!("  {:>2}: {}", i, hallmark.title);
+        }
+        let header: Vec<String> = (0..DEFAULT_HALLMARKS.len())
+            .map(|j| format!("{:>5}", j))
+            .collect();
+        println!("      {}", header.join(" "));
+        for (i, row) in self.hallmark_correlations().iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|c| format!("{:>5.2}", c)).collect();
+            println!("  {:>2}: {}", i, cells.join(" "));
+        }
+    }
+
+    /// The `n` keywords with the highest rating for `hallmark`, best first. Ties are broken
+    /// alphabetically and keywords without a rating for the hallmark are never listed.
+    pub fn top_keywords(&self, hallmark: usize, n: usize) -> Vec<(String, f32)> {
+        let mut ret: Vec<(String, f32)> = self
+            .keywords_map
+            .iter()
+            .filter(|(_, index)| self.is_rating_non_zero(**index, hallmark))
+            .map(|(keyword, index)| (keyword.clone(), self.keyword_ratings[hallmark][*index]))
+            .filter(|(_, rating)| *rating > 0.0)
+            .collect();
+        ret.sort_by(|a, b| by_descending_score((a.1, &a.0), (b.1, &b.0)));
+        ret.truncate(n);
+        ret
+    }
+
+    /// The keywords among the `n` top keywords of both hallmarks, ordered by their combined
+    /// rating for the two. These point at concepts bridging the hallmarks.
+    pub fn shared_top_keywords(&self, a: usize, b: usize, n: usize) -> Vec<String> {
+        let top_b: HashMap<String, f32> = self.top_keywords(b, n).into_iter().collect();
+        let mut shared: Vec<(String, f32)> = self
+            .top_keywords(a, n)
+            .into_iter()
+            .filter_map(|(keyword, rating)| {
+                let other = top_b.get(&keyword)?;
+                Some((keyword, rating + other))
+            })
+            .collect();
+        shared.sort_by(|x, y| by_descending_score((x.1, &x.0), (y.1, &y.0)));
+        shared.into_iter().map(|(keyword, _)| keyword).collect()
+    }
+
+    pub fn print_shared_top_keywords(&self, a: usize, b: usize, n: usize) {
+        let shared = self.shared_top_keywords(a, b, n);
+        println!(
+            "{} of the top {} keywords are shared by '{}' and '{}': {}",
+            shared.len(),
+            n,
+            DEFAULT_HALLMARKS[a].title,
+            DEFAULT_HALLMARKS[b].title,
+            shared.join(", ")
+        );
+    }
+
+    /// The per-hallmark rating of `keyword`, indexed like `DEFAULT_HALLMARKS`, or `None` if
+    /// it is not in the vocabulary.
+    pub fn keyword_profile(&self, keyword: &str) -> Option<Vec<f32>> {
+        self.keywords_map
+            .get(keyword)
+            .map(|index| self.rating_of(*index))
+    }
+
+    /// Like `keyword_profile`, with each rating paired with its hallmark title.
+    pub fn labeled_keyword_profile(&self, keyword: &str) -> Option<Vec<(&'static str, f32)>> {
+        let profile = self.keyword_profile(keyword)?;
+        Some(
+            DEFAULT_HALLMARKS
+                .iter()
+                .map(|h| h.title)
+                .zip(profile)
+                .collect(),
+        )
+    }
+
+    fn rating_of(&self, word: usize) -> Vec<f32> {
+        let mut rating: Vec<f32> = vec![];
+        for i in 0..DEFAULT_HALLMARKS.len() {
+            if self.is_rating_non_zero(word, i) {
+                rating.push(self.keyword_ratings[i][word]);
+            } else {
+                rating.push(0.0);
+            }
+        }
+        rating
     }
 
     fn is_rating_non_zero(&self, word: usize, hallmark: usize) -> bool {
         self.keyword_ratings[hallmark].nnz_index(word).is_some()
     }
 
-    fn normalize_keyword_rating(&mut self) -> usize {
+    /// Divides the hallmark ratings of every keyword by their sum and returns the number of
+    /// keywords without any rating. `compute_keyword_ratings` calls this after seeding and
+    /// after propagating; calling it again leaves the ratings as they are.
+    pub fn normalize_keyword_rating(&mut self) -> usize {
+        if self.config.keyword_major_normalization {
+            return self.normalize_keyword_rating_keyword_major();
+        }
         let mut number_of_unrated_words = 0;
         for i in 0..self.n_keywords {
             let mut sum = 0.0;
             for j in 0..DEFAULT_HALLMARKS.len() {
                 if self.is_rating_non_zero(i, j) {
-                    sum = sum + self.keyword_ratings[j][i];
+                    sum += self.keyword_ratings[j][i];
                 }
             }
             if sum > 0.0 {
@@ -201,45 +1201,236 @@ impl AnalyzerData {
                     }
                 }
             } else {
-                number_of_unrated_words = number_of_unrated_words + 1;
+                number_of_unrated_words += 1;
+            }
+        }
+        number_of_unrated_words
+    }
+
+    /// `normalize_keyword_rating` on a dense copy with one row of hallmark ratings per
+    /// keyword. Entries missing from the sparse vectors are 0 in the copy and stay missing
+    /// when it is written back, so the sparse structure is kept.
+    fn normalize_keyword_rating_keyword_major(&mut self) -> usize {
+        let n_hallmarks = self.keyword_ratings.len();
+        let mut dense = vec![0.0; self.n_keywords * n_hallmarks];
+        for (j, rating) in self.keyword_ratings.iter().enumerate() {
+            for (i, value) in rating.iter() {
+                dense[i * n_hallmarks + j] = *value;
+            }
+        }
+        let number_of_unrated_words = dense
+            .par_chunks_mut(n_hallmarks)
+            .map(|row| {
+                let sum: f32 = row.iter().sum();
+                if sum > 0.0 {
+                    for value in row.iter_mut().filter(|v| **v > 0.0) {
+                        *value /= sum;
+                    }
+                    0
+                } else {
+                    1
+                }
+            })
+            .sum();
+        for (j, rating) in self.keyword_ratings.iter_mut().enumerate() {
+            for (i, value) in rating.iter_mut() {
+                *value = dense[i * n_hallmarks + j];
             }
         }
         number_of_unrated_words
     }
 
     pub fn rate_article_keywords(&self, words: Vec<String>, id: String) -> RatedPublication {
-        let mut rating: Vec<f32> = vec![];
-        let mut hm = HashMap::new();
-        for word in words.iter().enumerate() {
-            let counter = hm.entry(word.1.to_string()).or_insert(0);
-            *counter += 1;
+        let mut accumulator = self.rating_accumulator();
+        for word in words.iter() {
+            accumulator.add_keyword(word, 1);
         }
-        for _i in 0..DEFAULT_HALLMARKS.len() {
-            rating.push(0.0);
+        accumulator.finalize(id)
+    }
+
+    /// Like `rate_article_keywords`, but only the hallmarks with the given indices are rated
+    /// and the rating is normalized over them. All other hallmarks are rated 0, so
+    /// `RatedPublication::is_valid` still tells whether the publication could be rated.
+    pub fn rate_article_keywords_for_hallmarks(
+        &self,
+        words: Vec<String>,
+        id: String,
+        hallmarks: &[usize],
+    ) -> RatedPublication {
+        let mut accumulator = self.rating_accumulator();
+        for word in words.iter() {
+            accumulator.add_keyword(word, 1);
+        }
+        RatedPublication {
+            r: accumulator.rating_for_hallmarks(hallmarks),
+            i: id,
+            c: None,
+        }
+    }
+
+    /// The occurrences of every vocabulary keyword among `words`, indexed like the relations
+    /// matrix. This is what `rate_article_keywords` weights and rates, so it can serve as
+    /// the input of other scorings. Tokens outside the vocabulary are skipped.
+    pub fn term_frequencies(&self, words: &[String]) -> CsVec<f32> {
+        let mut counts: BTreeMap<usize, f32> = BTreeMap::new();
+        for word in words.iter() {
+            if let Some(keyword_index) = self.keywords_map.get(word) {
+                *counts.entry(*keyword_index).or_insert(0.0) += 1.0;
+            }
         }
+        let (indices, data) = counts.into_iter().unzip();
+        CsVec::new(self.n_keywords, indices, data)
+    }
+
+    /// The vocabulary keywords of `text` with their number of occurrences, tokenized like
+    /// the training abstracts. Tokens outside the vocabulary are dropped, so callers can
+    /// apply their own weighting to exactly what `rate_article_keywords` would see.
+    pub fn vocab_filter(&self, text: &str) -> HashMap<String, usize> {
+        self.tokenizer
+            .token_counts(text)
+            .into_iter()
+            .filter(|(token, _)| self.keywords_map.contains_key(token))
+            .collect()
+    }
+
+    /// An empty `RatingAccumulator` for rating a publication whose tokens arrive one by one.
+    pub fn rating_accumulator(&self) -> RatingAccumulator<'_> {
+        RatingAccumulator {
+            data: self,
+            counts: HashMap::new(),
+        }
+    }
+}
+
+/// Incremental form of `AnalyzerData::rate_article_keywords`: tokens are added as they
+/// arrive, and `finalize` yields the same publication rating as rating all of them at once.
+/// Counts of a repeated token are combined before `tf_weighting` applies, so a token may be
+/// added in several steps.
+pub struct RatingAccumulator<'a> {
+    data: &'a AnalyzerData,
+    /// Occurrences of each vocabulary keyword added so far, by keyword index.
+    counts: HashMap<usize, usize>,
+}
+
+impl RatingAccumulator<'_> {
+    pub fn add_keyword(&mut self, word: &str, count: usize) {
+        if let Some(keyword_index) = self.data.keywords_map.get(word) {
+            *self.counts.entry(*keyword_index).or_insert(0) += count;
+        }
+    }
+
+    /// The rating of the tokens added so far, normalized to sum to 1. All components are
+    /// NaN while none of the tokens has a rating, or while fewer distinct vocabulary
+    /// keywords than `min_relevant_keywords` were added.
+    pub fn rating(&self) -> Vec<f32> {
+        self.rating_with(&[true; DEFAULT_HALLMARKS.len()])
+    }
+
+    /// Like `rating`, restricted to the hallmarks with the given indices. Panics if an index
+    /// is out of range.
+    pub fn rating_for_hallmarks(&self, hallmarks: &[usize]) -> Vec<f32> {
+        let mut included = [false; DEFAULT_HALLMARKS.len()];
+        for hallmark in hallmarks.iter() {
+            included[*hallmark] = true;
+        }
+        self.rating_with(&included)
+    }
 
+    /// Number of distinct vocabulary keywords added so far.
+    pub fn n_relevant_keywords(&self) -> usize {
+        self.counts.len()
+    }
+
+    fn rating_with(&self, included: &[bool]) -> Vec<f32> {
+        let data = self.data;
+        if self.n_relevant_keywords() < data.config.min_relevant_keywords {
+            return vec![f32::NAN; DEFAULT_HALLMARKS.len()];
+        }
+        let mut rating: Vec<f32> = vec![0.0; DEFAULT_HALLMARKS.len()];
+        let n_keyword_tokens: usize = self.counts.values().sum();
         let mut sum = 0.0;
-        for word in hm {
-            let index_opt = self.keywords_map.get(&word.0);
-            if index_opt.is_some() {
-                let keyword_index = index_opt.unwrap();
-                for hallmark in 0..DEFAULT_HALLMARKS.len() {
-                    if self.is_rating_non_zero(*keyword_index, hallmark) {
-                        let component = self.keyword_ratings[hallmark][*keyword_index]
-                            * f32::sqrt(word.1 as f32);
-                        rating[hallmark] += component;
-                        sum += component;
+        for (keyword_index, count) in self.counts.iter() {
+            let weight = term_weight(&data.config, *count, n_keyword_tokens);
+            for (hallmark, value) in rating.iter_mut().enumerate() {
+                if included[hallmark] && data.is_rating_non_zero(*keyword_index, hallmark) {
+                    let mut component = data.keyword_ratings[hallmark][*keyword_index] * weight;
+                    if data.config.idf_weighting {
+                        component *= data.inverse_document_frequency(*keyword_index);
                     }
+                    *value += component;
+                    sum += component;
                 }
             }
         }
 
-        for i in 0..DEFAULT_HALLMARKS.len() {
-            rating[i] = rating[i] / sum;
+        for value in rating.iter_mut() {
+            *value /= sum;
         }
+        rating
+    }
+
+    pub fn finalize(self, id: String) -> RatedPublication {
         RatedPublication {
-            i: id.clone(),
-            r: rating,
+            r: self.rating(),
+            i: id,
+            c: None,
+        }
+    }
+}
+
+/// The weight of a keyword occurring `count` times among the `n_keyword_tokens` keyword
+/// tokens of an abstract, before any inverse document frequency: `tf_weighting` of the
+/// count, or of its share of the tokens with `length_normalization`.
+pub(crate) fn term_weight(config: &AnalyzerConfig, count: usize, n_keyword_tokens: usize) -> f32 {
+    if config.length_normalization && n_keyword_tokens > 0 {
+        config
+            .tf_weighting
+            .weight_frequency(count as f32 / n_keyword_tokens as f32)
+    } else {
+        config.tf_weighting.weight_frequency(count as f32)
+    }
+}
+
+impl PublicationRater for AnalyzerData {
+    fn rate_article_keywords(&self, words: Vec<String>, id: String) -> RatedPublication {
+        AnalyzerData::rate_article_keywords(self, words, id)
+    }
+
+    fn has_enough_keywords(&self, words: &[String]) -> bool {
+        let minimum = self.config.min_relevant_keywords;
+        if minimum == 0 {
+            return true;
         }
+        let relevant: HashSet<&str> = words
+            .iter()
+            .map(|word| word.as_str())
+            .filter(|word| self.keywords_map.contains_key(*word))
+            .collect();
+        relevant.len() >= minimum
+    }
+}
+
+/// What one abstract adds to the relations matrix entry of a keyword pair, given the
+/// occurrences of the first keyword and the `cooccurrence_weighting` weights of both.
+pub(crate) fn cooccurrence_increment(
+    config: &AnalyzerConfig,
+    is_diagonal: bool,
+    count: usize,
+    weight_i: f32,
+    weight_j: f32,
+) -> f32 {
+    match config.diagonal_count {
+        DiagonalCount::Abstracts if is_diagonal => 1.0,
+        DiagonalCount::Occurrences if is_diagonal => count as f32,
+        _ => config.cooccurrence_pairing.combine(weight_i, weight_j),
+    }
+}
+
+/// The representative of `node` in a union-find forest, halving the path on the way.
+fn component_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
     }
+    node
 }
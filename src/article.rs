@@ -1,11 +1,170 @@
+use serde::de::{Deserializer, SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::{BufReader, Read};
 
+use crate::error::InputError;
+
+/// One publication of the input files. Besides the field names written by the previous
+/// step, common spellings of other exporters are accepted when reading: upper case ids like
+/// `PMID`, `pmcid` for `pmc`, and `abstract` for `paper_abstract`. Articles are always
+/// written with the field names below.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Article {
+    #[serde(alias = "Title")]
     pub title: String,
+    #[serde(alias = "PMID")]
     pub pmid: String,
+    #[serde(alias = "DOI")]
     pub doi: String,
+    #[serde(alias = "PMC", alias = "pmcid", alias = "PMCID")]
     pub pmc: String,
+    #[serde(alias = "PII")]
     pub pii: String,
+    #[serde(alias = "abstract", alias = "Abstract", alias = "paperAbstract")]
     pub paper_abstract: String,
 }
+
+/// Reads one input file, a JSON array of articles. Every input file is expected to be a
+/// complete array on its own; use `InputSource` for arrays split across files.
+pub fn read_articles(filename: &str) -> Result<Vec<Article>, InputError> {
+    read_file(filename, None)
+}
+
+/// An array element that is valid JSON but no article, e.g. because a field is missing.
+#[derive(Debug)]
+pub struct MalformedArticle {
+    /// Position of the element in its array.
+    pub index: usize,
+    pub error: serde_json::Error,
+}
+
+fn read_file(
+    filename: &str,
+    skipped: Option<&mut Vec<MalformedArticle>>,
+) -> Result<Vec<Article>, InputError> {
+    let file_contents = fs::read(filename).map_err(|source| InputError::Io {
+        file: filename.to_string(),
+        source,
+    })?;
+    parse_articles(
+        &mut serde_json::Deserializer::from_slice(&file_contents),
+        filename,
+        skipped,
+    )
+}
+
+/// Parses a JSON array of articles from `de`, naming `file` and the index of the offending
+/// article in errors. If `skipped` is given, elements that are valid JSON but no article
+/// are collected there instead of failing the whole array.
+fn parse_articles<'de, R: serde_json::de::Read<'de>>(
+    de: &mut serde_json::Deserializer<R>,
+    file: &str,
+    skipped: Option<&mut Vec<MalformedArticle>>,
+) -> Result<Vec<Article>, InputError> {
+    let mut n_parsed = None;
+    let parse_error = |n_parsed: Option<usize>, source| InputError::Parse {
+        file: file.to_string(),
+        article: n_parsed,
+        source,
+    };
+    let articles = de
+        .deserialize_seq(ArticleArray {
+            n_parsed: &mut n_parsed,
+            skipped,
+        })
+        .map_err(|e| parse_error(n_parsed, e))?;
+    de.end().map_err(|e| parse_error(None, e))?;
+    Ok(articles)
+}
+
+/// Visits the elements of an array of articles one by one, keeping track of how many were
+/// parsed so a failure can be attributed to its article.
+struct ArticleArray<'a> {
+    n_parsed: &'a mut Option<usize>,
+    skipped: Option<&'a mut Vec<MalformedArticle>>,
+}
+
+impl<'de> Visitor<'de> for ArticleArray<'_> {
+    type Value = Vec<Article>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of articles")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Article>, A::Error> {
+        let mut articles = vec![];
+        let mut index = 0;
+        *self.n_parsed = Some(index);
+        match self.skipped {
+            None => {
+                while let Some(article) = seq.next_element::<Article>()? {
+                    articles.push(article);
+                    index += 1;
+                    *self.n_parsed = Some(index);
+                }
+            }
+            Some(skipped) => {
+                while let Some(value) = seq.next_element::<serde_json::Value>()? {
+                    match Article::deserialize(value) {
+                        Ok(article) => articles.push(article),
+                        Err(error) => skipped.push(MalformedArticle { index, error }),
+                    }
+                    index += 1;
+                    *self.n_parsed = Some(index);
+                }
+            }
+        }
+        Ok(articles)
+    }
+}
+
+/// One unit of input holding a JSON array of articles: usually a single file, or several
+/// files that only form a valid array when concatenated in order, e.g. a chunked export.
+#[derive(Debug, Clone)]
+pub struct InputSource {
+    pub files: Vec<String>,
+}
+
+impl InputSource {
+    pub fn name(&self) -> String {
+        self.files.join(" + ")
+    }
+
+    pub fn read(&self) -> Result<Vec<Article>, InputError> {
+        self.parse(None)
+    }
+
+    /// Like `read`, but skips array elements that are valid JSON yet no article and returns
+    /// them separately. Malformed JSON still fails the whole source.
+    pub fn read_skipping_malformed(
+        &self,
+    ) -> Result<(Vec<Article>, Vec<MalformedArticle>), InputError> {
+        let mut skipped = vec![];
+        let articles = self.parse(Some(&mut skipped))?;
+        Ok((articles, skipped))
+    }
+
+    fn parse(
+        &self,
+        skipped: Option<&mut Vec<MalformedArticle>>,
+    ) -> Result<Vec<Article>, InputError> {
+        if let [filename] = self.files.as_slice() {
+            return read_file(filename, skipped);
+        }
+        let mut reader: Box<dyn Read> = Box::new(std::io::empty());
+        for filename in self.files.iter() {
+            let file = fs::File::open(filename).map_err(|source| InputError::Io {
+                file: filename.clone(),
+                source,
+            })?;
+            reader = Box::new(reader.chain(BufReader::new(file)));
+        }
+        parse_articles(
+            &mut serde_json::Deserializer::from_reader(reader),
+            &self.name(),
+            skipped,
+        )
+    }
+}
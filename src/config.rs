@@ -0,0 +1,580 @@
+use std::collections::{HashMap, HashSet};
+
+/// How internal hyphens of compound words like "non-small-cell" are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HyphenMode {
+    /// Keep compound words as one token.
+    #[default]
+    Keep,
+    /// Replace compound words by their parts.
+    Split,
+    /// Emit the compound word as well as its parts.
+    Both,
+}
+
+impl std::str::FromStr for HyphenMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(HyphenMode::Keep),
+            "split" => Ok(HyphenMode::Split),
+            "both" => Ok(HyphenMode::Both),
+            _ => Err(format!("unknown hyphen mode '{}'", s)),
+        }
+    }
+}
+
+/// How the number of occurrences of a keyword in an abstract is damped when rating it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TfWeighting {
+    /// The square root of the count.
+    #[default]
+    Sqrt,
+    /// The count itself.
+    Raw,
+    /// `ln(1 + count)`.
+    Log,
+    /// 1 for every keyword present, regardless of its count.
+    Binary,
+}
+
+impl TfWeighting {
+    pub fn weight(&self, count: usize) -> f32 {
+        self.weight_frequency(count as f32)
+    }
+
+    /// Like `weight`, for a count that may be fractional, e.g. a share of the tokens of an
+    /// abstract.
+    pub fn weight_frequency(&self, frequency: f32) -> f32 {
+        match self {
+            TfWeighting::Sqrt => frequency.sqrt(),
+            TfWeighting::Raw => frequency,
+            TfWeighting::Log => frequency.ln_1p(),
+            TfWeighting::Binary => 1.0,
+        }
+    }
+}
+
+impl std::str::FromStr for TfWeighting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sqrt" => Ok(TfWeighting::Sqrt),
+            "raw" => Ok(TfWeighting::Raw),
+            "log" => Ok(TfWeighting::Log),
+            "binary" => Ok(TfWeighting::Binary),
+            _ => Err(format!("unknown term frequency weighting '{}'", s)),
+        }
+    }
+}
+
+/// How the `cooccurrence_weighting` weights of two keywords in the same abstract combine into
+/// what the abstract adds to their pair. With the default binary weighting both modes add 1
+/// per abstract; with raw weights they add `tf_a * tf_b` or `min(tf_a, tf_b)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CooccurrencePairing {
+    /// `w_a * w_b`, so a pair counts as often as its repetitions can be matched up.
+    #[default]
+    Product,
+    /// `min(w_a, w_b)`, so a pair counts as often as its rarer keyword occurs.
+    Minimum,
+}
+
+impl CooccurrencePairing {
+    pub fn combine(&self, weight_a: f32, weight_b: f32) -> f32 {
+        match self {
+            CooccurrencePairing::Product => weight_a * weight_b,
+            CooccurrencePairing::Minimum => weight_a.min(weight_b),
+        }
+    }
+}
+
+impl std::str::FromStr for CooccurrencePairing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(CooccurrencePairing::Product),
+            "min" => Ok(CooccurrencePairing::Minimum),
+            _ => Err(format!("unknown co-occurrence pairing '{}'", s)),
+        }
+    }
+}
+
+/// What loading a rating database does if it was trained with other hallmarks than
+/// `DEFAULT_HALLMARKS`, see `RatingModel::load_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HallmarkMismatch {
+    /// Fail to load the database.
+    #[default]
+    Refuse,
+    /// Rate with the hallmarks of the database, so publication ratings have one component
+    /// per hallmark the model was trained with.
+    Adapt,
+}
+
+impl std::str::FromStr for HallmarkMismatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "refuse" => Ok(HallmarkMismatch::Refuse),
+            "adapt" => Ok(HallmarkMismatch::Adapt),
+            _ => Err(format!("unknown hallmark mismatch behavior '{}'", s)),
+        }
+    }
+}
+
+/// What a run does when keyword or publication ratings turn out NaN or infinite, see
+/// `AnalyzerData::non_finite_ratings` and `RatedPublication::has_non_finite_rating`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFiniteHandling {
+    /// Report where the values are and go on. Publications with such ratings are dropped.
+    #[default]
+    Warn,
+    /// Report them and fail the run with `AnalyzerError::NonFiniteRatings`.
+    Fail,
+}
+
+impl std::str::FromStr for NonFiniteHandling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(NonFiniteHandling::Warn),
+            "fail" => Ok(NonFiniteHandling::Fail),
+            _ => Err(format!("unknown non-finite rating handling '{}'", s)),
+        }
+    }
+}
+
+/// What the diagonal of the relations matrix counts for each keyword, see
+/// `AnalyzerData::update_with_article_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiagonalCount {
+    /// The keyword paired with itself like any other pair, adding the square of its
+    /// `cooccurrence_weighting` per abstract, or the weight itself with
+    /// `CooccurrencePairing::Minimum`. With the default binary weighting this is the number
+    /// of abstracts containing the keyword.
+    #[default]
+    Weighted,
+    /// The number of abstracts containing the keyword, whatever the weighting.
+    Abstracts,
+    /// The total number of occurrences of the keyword in all abstracts.
+    Occurrences,
+}
+
+impl std::str::FromStr for DiagonalCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weighted" => Ok(DiagonalCount::Weighted),
+            "abstracts" => Ok(DiagonalCount::Abstracts),
+            "occurrences" => Ok(DiagonalCount::Occurrences),
+            _ => Err(format!("unknown diagonal count '{}'", s)),
+        }
+    }
+}
+
+/// How `divide_rows_by_diagonal` normalizes the co-occurrence counts of the relations matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowNormalization {
+    /// The diagonal entry of the row, by default the number of abstracts containing the
+    /// row's keyword, see `DiagonalCount`.
+    #[default]
+    Diagonal,
+    /// `ln(1 + count)`, so rows of very common keywords keep larger weights.
+    LogDiagonal,
+    /// Positive pointwise mutual information `max(0, ln(N * c / (df_a * df_b)))` of a pair
+    /// co-occurring in `c` of `N` abstracts, from the document frequencies of the candidate
+    /// scan. It rewards surprising rather than frequent pairs, and the matrix stays symmetric.
+    /// `c` is only a number of abstracts with the default binary `cooccurrence_weighting`.
+    Ppmi,
+}
+
+impl std::str::FromStr for RowNormalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "diagonal" => Ok(RowNormalization::Diagonal),
+            "log" => Ok(RowNormalization::LogDiagonal),
+            "ppmi" => Ok(RowNormalization::Ppmi),
+            _ => Err(format!("unknown row normalization '{}'", s)),
+        }
+    }
+}
+
+/// The matrix that propagates keyword ratings to co-occurring keywords. After
+/// `divide_rows_by_diagonal`, entry `(a, b)` of the relations matrix is the share of the
+/// abstracts of `a` that also contain `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PropagationOperator {
+    /// The normalized relations matrix itself. A keyword takes the average rating of its
+    /// partners, weighted by how often they appear in its abstracts, so ratings flow into
+    /// every keyword from the frequent keywords around it.
+    #[default]
+    Rows,
+    /// The transposed matrix. A keyword passes its rating on to its partners in proportion
+    /// to their share of its own abstracts, so frequent keywords spread their ratings thinly
+    /// and rare keywords count more for the partners they do have.
+    Transposed,
+    /// The mean of the matrix and its transpose, halfway between both.
+    Symmetrized,
+}
+
+impl std::str::FromStr for PropagationOperator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(PropagationOperator::Rows),
+            "transposed" => Ok(PropagationOperator::Transposed),
+            "symmetrized" => Ok(PropagationOperator::Symmetrized),
+            _ => Err(format!("unknown propagation operator '{}'", s)),
+        }
+    }
+}
+
+/// Patterns for statistical notation that `TokenizerConfig::protected_patterns` can use.
+pub const STATISTICAL_PATTERNS: [&str; 2] = [
+    r"p\s*[<>=≤≥]\s*0?\.\d+",
+    r"\d+(\.\d+)?\s?(%|(mg|ml|kg|nm|µm|mm)\b)",
+];
+
+/// Words replacing percent and unit symbols when `TokenizerConfig::unit_words` is set.
+/// Longer symbols come first so they win over their prefixes. Both the micro sign µ and the
+/// Greek letter μ are listed, since abstracts use either.
+pub const UNIT_WORDS: [(&str, &str); 9] = [
+    ("°c", "celsius"),
+    ("µm", "micrometer"),
+    ("µg", "microgram"),
+    ("µl", "microliter"),
+    ("μm", "micrometer"),
+    ("μg", "microgram"),
+    ("μl", "microliter"),
+    ("%", "percent"),
+    ("‰", "permille"),
+];
+
+/// Latin names of the lowercase Greek letters, for `TokenizerConfig::transliterations`.
+pub const GREEK_LETTERS: [(char, &str); 24] = [
+    ('α', "alpha"),
+    ('β', "beta"),
+    ('γ', "gamma"),
+    ('δ', "delta"),
+    ('ε', "epsilon"),
+    ('ζ', "zeta"),
+    ('η', "eta"),
+    ('θ', "theta"),
+    ('ι', "iota"),
+    ('κ', "kappa"),
+    ('λ', "lambda"),
+    ('μ', "mu"),
+    ('ν', "nu"),
+    ('ξ', "xi"),
+    ('ο', "omicron"),
+    ('π', "pi"),
+    ('ρ', "rho"),
+    ('σ', "sigma"),
+    ('τ', "tau"),
+    ('υ', "upsilon"),
+    ('φ', "phi"),
+    ('χ', "chi"),
+    ('ψ', "psi"),
+    ('ω', "omega"),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenizerConfig {
+    pub hyphen_mode: HyphenMode,
+    /// Regular expressions matched against the lowercased text before punctuation is
+    /// removed. Matches are not shredded into fragments like "05".
+    pub protected_patterns: Vec<String>,
+    /// Drop protected matches instead of keeping them as single tokens.
+    pub drop_protected: bool,
+    /// Aliases mapped to their canonical token, e.g. "neoplasm" to "tumor". Applied to
+    /// every token after filtering, so aliases collapse into one keyword.
+    pub synonyms: HashMap<String, String>,
+    /// Surface forms mapped to their dictionary form, e.g. "cells" to "cell". Applied to
+    /// every token after filtering and before `synonyms`; unknown words pass unchanged.
+    /// Unlike stemming this never yields non-words, so the keywords stay readable.
+    pub lemmas: HashMap<String, String>,
+    /// Characters replaced by a Latin spelling right after lowercasing, so e.g. "β-catenin"
+    /// becomes the keyword "beta-catenin". See `GREEK_LETTERS`.
+    pub transliterations: HashMap<char, String>,
+    /// Replace percent signs and unit symbols by words like "percent" (see `UNIT_WORDS`)
+    /// instead of stripping them with the punctuation. Only symbols standing on their own or
+    /// right after a number are replaced, right after lowercasing, so `transliterations`
+    /// and `protected_patterns` see the words instead of the symbols.
+    pub unit_words: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzerConfig {
+    /// Keywords found in at most `lower_cutoff * n_files` abstracts are purged.
+    pub lower_cutoff: f32,
+    /// Keywords found in at least `upper_cutoff * n_files` abstracts are purged.
+    pub upper_cutoff: f32,
+    /// Choose `lower_cutoff` from the candidate counts so that about this many keywords
+    /// survive the cutoffs.
+    pub target_vocabulary_size: Option<usize>,
+    /// Keep at most this many of the keywords surviving the cutoffs, the ones found in the
+    /// most abstracts, so the relations matrix has a predictable size. Allowlisted keywords
+    /// count against the cap like any other.
+    pub max_vocabulary_size: Option<usize>,
+    /// Curated seed keywords per hallmark title. A hallmark listed here is seeded from
+    /// exactly these keywords instead of the tokenized description.
+    pub seed_keywords: HashMap<String, Vec<String>>,
+    /// Phrases mapped to the keywords they seed. A hallmark whose description contains a
+    /// phrase, or whose explicit seeds list it, is additionally seeded from its keywords.
+    pub seed_synonyms: HashMap<String, Vec<String>>,
+    /// Input files to process. When empty, the files are discovered in the working
+    /// directory using `input_file_template`.
+    pub input_files: Vec<String>,
+    /// Treat all input files as chunks of one JSON array, concatenated in order, instead of
+    /// one complete array per file.
+    pub concatenated_input: bool,
+    /// Process only the first this many training input files, for quick test runs.
+    pub file_limit: Option<usize>,
+    /// Files whose publications are rated. When empty, the training input files are rated.
+    pub rating_input_files: Vec<String>,
+    /// Template of the input file names. The `{}` placeholder is replaced by the file
+    /// index; `{:0>N}` pads the index with zeros to `N` digits.
+    pub input_file_template: String,
+    /// Index of the first input file.
+    pub first_input_index: usize,
+    /// Path of the keyword rating database.
+    pub rating_output_path: String,
+    /// Path of the rated article database.
+    pub article_output_path: String,
+    /// Rating databases of previously trained models. When set, no model is trained and the
+    /// publications are rated by the average of these models, weighing keywords as a
+    /// trained model does, see `RatingModel::rate`. `idf_weighting` then needs an
+    /// `idf_table`, as rating databases store no document frequencies.
+    pub ensemble_models: Vec<String>,
+    /// What happens if an ensemble model was trained with other hallmarks than the active
+    /// ones. All models of an ensemble need the same hallmarks either way.
+    pub hallmark_mismatch: HallmarkMismatch,
+    /// Write the keyword candidates and their counts before the cutoffs to this path.
+    pub candidate_output_path: Option<String>,
+    /// Write the keyword of every row and column of the relations matrix to this path.
+    pub keyword_index_output_path: Option<String>,
+    /// Write the vocabulary left by the cutoffs to this path, one keyword per line, before
+    /// the relations matrix is built.
+    pub vocabulary_output_path: Option<String>,
+    /// Write the size, density and keyword histogram of the relations matrix to this path
+    /// once it is built, before normalization.
+    pub matrix_stats_output_path: Option<String>,
+    /// Write the keyword ratings after seeding, before they are propagated through the
+    /// relations matrix, to this path in the format of the rating database.
+    pub seed_rating_output_path: Option<String>,
+    /// Write rated publications to the article database as they are rated instead of
+    /// collecting them in memory first.
+    pub stream_article_output: bool,
+    /// Keywords that survive `purge_keyword_array` regardless of the cutoffs.
+    pub keyword_allowlist: HashSet<String>,
+    /// Keywords that are always purged. The denylist wins over the allowlist.
+    pub keyword_denylist: HashSet<String>,
+    /// Stop after building the relations matrix and write it, unnormalized, to this path.
+    pub shard_output: Option<String>,
+    /// Shards written with `shard_output` that are merged instead of scanning the input
+    /// files. The input files are still used to rate publications.
+    pub shard_inputs: Vec<String>,
+    /// Tokenization settings, applied identically to abstracts and hallmark descriptions.
+    pub tokenizer: TokenizerConfig,
+    /// Suppress progress bars and phase status lines. The run summary is still printed.
+    pub quiet: bool,
+    /// Let progress bars advance per article instead of per file. The articles are counted
+    /// during the candidate scan, which itself advances per file. Rating input that the scan
+    /// did not read, e.g. `rating_input_files`, is counted in a pre-pass.
+    pub article_progress: bool,
+    /// Keep the tokens of every abstract in memory after the candidate scan, so building the
+    /// relations matrix neither reads the input files again nor tokenizes. Only tokens that
+    /// survive the cutoffs are kept, but memory still grows with the corpus.
+    pub cache_tokens: bool,
+    /// Record the co-occurrences of all keyword candidates during the candidate scan and
+    /// build the relations matrix from them after the cutoffs, so every input file is read
+    /// only once. Pairs of candidates that do not survive the cutoffs are recorded too, so
+    /// this needs far more memory than `cache_tokens`, which it supersedes.
+    pub single_pass: bool,
+    /// Print this many of the largest clusters of keyword candidates that only differ by
+    /// hyphens or plural endings, right after the candidate scan.
+    pub variant_report: Option<usize>,
+    /// Weight of a keyword repeated within an abstract when counting co-occurrences. The
+    /// default binary mode counts each abstract once per keyword pair.
+    pub cooccurrence_weighting: TfWeighting,
+    /// How the weights of both keywords of a pair combine.
+    pub cooccurrence_pairing: CooccurrencePairing,
+    /// What the diagonal of the relations matrix counts. It is the divisor of the default
+    /// row normalization. Has no effect with `exclude_self_pairs`.
+    pub diagonal_count: DiagonalCount,
+    /// Print the cosine similarities between the hallmarks' keyword ratings after training.
+    pub hallmark_correlations: bool,
+    /// Two hallmark indices whose shared top keywords are printed after training.
+    pub shared_keywords: Option<(usize, usize)>,
+    /// How many of the highest rated keywords of a hallmark count as its top keywords.
+    pub top_keywords: usize,
+    /// Divisor of each row of the relations matrix during normalization.
+    pub row_normalization: RowNormalization,
+    /// Leave the diagonal of the relations matrix empty instead of counting each keyword as
+    /// co-occurring with itself. Rows are then normalized by the document frequencies, and
+    /// a keyword's own rating no longer feeds into its propagated rating.
+    pub exclude_self_pairs: bool,
+    /// Leave out articles with fewer distinct vocabulary keywords than this, both from the
+    /// relations matrix and from rating, as so few keywords give unreliable ratings. The
+    /// skipped articles are counted in the run summary. With a minimum, `single_pass` falls
+    /// back to two passes, since the vocabulary of an article is only known after the scan.
+    pub min_relevant_keywords: usize,
+    /// Let `AnalyzerData::add_articles` add terms outside the vocabulary that occur in at
+    /// least this many of the added abstracts, unless they are denylisted. `None` keeps the
+    /// vocabulary fixed.
+    pub vocabulary_growth: Option<usize>,
+    /// Which form of the normalized relations matrix propagates the keyword ratings.
+    pub propagation: PropagationOperator,
+    /// Ignore the diagonal of the relations matrix when propagating ratings, so a keyword's
+    /// rating comes only from its neighbors. Keywords without co-occurring partners end up
+    /// unrated, and with more propagation steps ratings can oscillate between groups of
+    /// keywords instead of settling.
+    pub exclude_self_loops: bool,
+    /// Normalize the keyword ratings in a dense keyword-major copy, with the hallmark
+    /// ratings of each keyword next to each other, and in parallel over the keywords. The
+    /// result is the same as in the hallmark-major sparse vectors.
+    pub keyword_major_normalization: bool,
+    /// Additionally weight each keyword of a rated publication by its inverse document
+    /// frequency, so ubiquitous keywords contribute less.
+    pub idf_weighting: bool,
+    /// Fixed inverse document frequencies by keyword, used by `idf_weighting` instead of
+    /// the document frequencies of the corpus, so scores stay comparable across model
+    /// versions. Keywords missing from the table weigh `default_idf`.
+    pub idf_table: Option<HashMap<String, f32>>,
+    pub default_idf: f32,
+    /// Damping of repeated keywords when rating publications.
+    pub tf_weighting: TfWeighting,
+    /// Divide the count of each keyword in an abstract by the number of vocabulary keyword
+    /// tokens of that abstract, including repetitions, before `tf_weighting` applies, so the
+    /// weighting sees the share of the abstract a keyword takes up rather than its count. A
+    /// common factor cancels in the normalization to sum 1, so the ratings only change with
+    /// `TfWeighting::Log`: `ln(1 + share)` is nearly linear for the small shares of long
+    /// abstracts and damps the large shares of short ones. With the default square root, as
+    /// with raw counts and binary weights, the ratings stay the same.
+    pub length_normalization: bool,
+    /// Write the `RatedPublication::confidence` of every rated publication to the article
+    /// database.
+    pub publication_confidence: bool,
+    /// Leave keywords out of the rating database whose highest hallmark rating is below this
+    /// value, and unrated keywords. All keywords are written if `None`.
+    pub min_output_rating: Option<f32>,
+    /// Only write keywords matching this regular expression to the rating database, e.g.
+    /// `immun` for every keyword containing it. Combines with `min_output_rating`. All
+    /// keywords are written if `None`.
+    pub output_keyword_pattern: Option<String>,
+    /// Number of threads of the parallel phases, all cores if `None`.
+    pub threads: Option<usize>,
+    /// Skip articles that are valid JSON but miss fields or have fields of the wrong type,
+    /// instead of aborting on the first one. Syntax errors still abort the run.
+    pub skip_malformed_articles: bool,
+    /// Skip articles whose abstract has fewer characters than this in every phase, before
+    /// tokenization. The skipped articles are counted in the run summary.
+    pub min_abstract_length: usize,
+    /// Print every article that is not rated because its abstract yields no tokens.
+    pub log_empty_abstracts: bool,
+    /// Check that every rated keyword's hallmark components sum to 1 before writing the
+    /// rating database, and warn about violations. Also checks that the relations matrix is
+    /// symmetric before normalization, which debug builds always do.
+    pub validate_ratings: bool,
+    /// What happens if keyword ratings after propagation, or publication ratings, contain
+    /// NaN or infinite values. They are always looked for.
+    pub non_finite_ratings: NonFiniteHandling,
+}
+
+impl Default for AnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            lower_cutoff: 0.05,
+            upper_cutoff: 0.25,
+            target_vocabulary_size: None,
+            max_vocabulary_size: None,
+            seed_keywords: HashMap::new(),
+            seed_synonyms: HashMap::new(),
+            input_files: vec![],
+            concatenated_input: false,
+            file_limit: None,
+            rating_input_files: vec![],
+            input_file_template: "results_pubmed24n{:0>4}.xml.json".to_string(),
+            first_input_index: 1,
+            rating_output_path: "rating_database.json".to_string(),
+            article_output_path: "article_database.json".to_string(),
+            ensemble_models: vec![],
+            hallmark_mismatch: HallmarkMismatch::Refuse,
+            candidate_output_path: None,
+            keyword_index_output_path: None,
+            vocabulary_output_path: None,
+            matrix_stats_output_path: None,
+            seed_rating_output_path: None,
+            stream_article_output: false,
+            keyword_allowlist: HashSet::new(),
+            keyword_denylist: HashSet::new(),
+            shard_output: None,
+            shard_inputs: vec![],
+            tokenizer: TokenizerConfig::default(),
+            quiet: false,
+            article_progress: false,
+            cache_tokens: false,
+            single_pass: false,
+            variant_report: None,
+            cooccurrence_weighting: TfWeighting::Binary,
+            cooccurrence_pairing: CooccurrencePairing::Product,
+            diagonal_count: DiagonalCount::Weighted,
+            hallmark_correlations: false,
+            shared_keywords: None,
+            top_keywords: 20,
+            row_normalization: RowNormalization::Diagonal,
+            exclude_self_pairs: false,
+            min_relevant_keywords: 0,
+            vocabulary_growth: None,
+            propagation: PropagationOperator::Rows,
+            exclude_self_loops: false,
+            keyword_major_normalization: false,
+            idf_weighting: false,
+            idf_table: None,
+            default_idf: 1.0,
+            tf_weighting: TfWeighting::Sqrt,
+            length_normalization: false,
+            publication_confidence: false,
+            skip_malformed_articles: false,
+            min_abstract_length: 0,
+            threads: None,
+            min_output_rating: None,
+            output_keyword_pattern: None,
+            log_empty_abstracts: false,
+            validate_ratings: false,
+            non_finite_ratings: NonFiniteHandling::Warn,
+        }
+    }
+}
+
+/// Fills the placeholder of an input file template with `index`.
+pub fn format_input_filename(template: &str, index: usize) -> String {
+    let (start, end) = match (template.find('{'), template.find('}')) {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => return template.to_string(),
+    };
+    let spec = &template[start + 1..end];
+    let width = spec
+        .trim_start_matches(':')
+        .trim_start_matches('0')
+        .trim_start_matches('>')
+        .parse::<usize>()
+        .unwrap_or(0);
+    format!(
+        "{}{:0>width$}{}",
+        &template[..start],
+        index,
+        &template[end + 1..],
+        width = width
+    )
+}
@@ -0,0 +1,241 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+use crate::analyzer::{PublicationRater, RatedPublication};
+use crate::analyzer_data::{term_weight, HallmarkRatingOutput};
+use crate::config::{AnalyzerConfig, HallmarkMismatch};
+use crate::error::InputError;
+use crate::output::{check_format_version, read_output_file};
+use crate::{default_hallmark_titles, DEFAULT_HALLMARKS};
+
+#[derive(Deserialize)]
+struct RatingDatabase {
+    /// Missing in databases written before versioning.
+    #[serde(default)]
+    format_version: u32,
+    /// Missing in databases written before the hallmarks were embedded, which are taken to
+    /// use `DEFAULT_HALLMARKS`.
+    #[serde(default)]
+    hallmarks: Option<Vec<StoredHallmark>>,
+    rating_output: Vec<HallmarkRatingOutput>,
+}
+
+#[derive(Deserialize)]
+struct StoredHallmark {
+    title: String,
+}
+
+/// The keyword ratings of one trained model, as read from a rating database.
+pub struct RatingModel {
+    /// Titles of the hallmarks the model was trained with, which index its ratings.
+    hallmarks: Vec<String>,
+    ratings: HashMap<String, Vec<f32>>,
+}
+
+impl RatingModel {
+    /// Loads a database trained with `DEFAULT_HALLMARKS`, failing for any other hallmarks.
+    pub fn load(path: &str) -> std::io::Result<RatingModel> {
+        RatingModel::load_with(path, HallmarkMismatch::Refuse)
+    }
+
+    /// Loads a database, handling one trained with other hallmarks than `DEFAULT_HALLMARKS`
+    /// as `mismatch` asks for. Every keyword needs one rating per hallmark of the database.
+    /// A `.gz` path is decompressed.
+    pub fn load_with(path: &str, mismatch: HallmarkMismatch) -> std::io::Result<RatingModel> {
+        let file_contents = read_output_file(path)?;
+        let database: RatingDatabase = serde_json::from_str(&file_contents)?;
+        check_format_version(path, database.format_version)?;
+        let active = default_hallmark_titles();
+        let hallmarks = match database.hallmarks {
+            Some(stored) => stored.into_iter().map(|h| h.title).collect(),
+            None => active.clone(),
+        };
+        if hallmarks != active && mismatch == HallmarkMismatch::Refuse {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "the model was trained with {} other hallmarks than the {} active ones: {}",
+                    hallmarks.len(),
+                    active.len(),
+                    hallmarks.join(", ")
+                ),
+            ));
+        }
+        let mut ratings = HashMap::new();
+        for entry in database.rating_output {
+            if entry.rating.len() != hallmarks.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "keyword '{}' has {} hallmark ratings instead of {}",
+                        entry.keyword,
+                        entry.rating.len(),
+                        hallmarks.len()
+                    ),
+                ));
+            }
+            ratings.insert(entry.keyword, entry.rating);
+        }
+        Ok(RatingModel { hallmarks, ratings })
+    }
+
+    pub fn hallmarks(&self) -> &[String] {
+        &self.hallmarks
+    }
+
+    /// The index of the hallmark titled `title` in the ratings of this model.
+    pub fn hallmark_index(&self, title: &str) -> Option<usize> {
+        self.hallmarks.iter().position(|h| h == title)
+    }
+
+    pub fn n_keywords(&self) -> usize {
+        self.ratings.len()
+    }
+
+    /// The hallmark ratings of `keyword`, indexed like `hallmarks`.
+    pub fn rating(&self, keyword: &str) -> Option<&[f32]> {
+        self.ratings.get(keyword).map(|r| r.as_slice())
+    }
+
+    pub fn keywords(&self) -> impl Iterator<Item = &str> {
+        self.ratings.keys().map(|k| k.as_str())
+    }
+
+    /// Number of distinct keywords of this model among `words`.
+    pub fn n_relevant_keywords(&self, words: &[String]) -> usize {
+        self.keyword_counts(words).len()
+    }
+
+    fn keyword_counts<'a>(&self, words: &'a [String]) -> HashMap<&'a str, usize> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for word in words.iter() {
+            if self.ratings.contains_key(word) {
+                *counts.entry(word.as_str()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// The normalized rating of a tokenized abstract, weighted like
+    /// `AnalyzerData::rate_article_keywords` with the `tf_weighting`, `length_normalization`
+    /// and `min_relevant_keywords` of `config`. As rating databases store no document
+    /// frequencies, `idf_weighting` takes them from `idf_table` and weighs every keyword 1
+    /// without one. `None` if no word, or fewer distinct words than `min_relevant_keywords`,
+    /// are rated keywords of this model.
+    pub fn rate(&self, words: &[String], config: &AnalyzerConfig) -> Option<Vec<f32>> {
+        let counts = self.keyword_counts(words);
+        if counts.len() < config.min_relevant_keywords {
+            return None;
+        }
+        let n_keyword_tokens: usize = counts.values().sum();
+        let mut rating = vec![0.0; self.hallmarks.len()];
+        for (word, count) in counts {
+            let mut weight = term_weight(config, count, n_keyword_tokens);
+            if config.idf_weighting {
+                if let Some(idf_table) = &config.idf_table {
+                    weight *= idf_table.get(word).copied().unwrap_or(config.default_idf);
+                }
+            }
+            for (value, r) in rating.iter_mut().zip(self.ratings[word].iter()) {
+                *value += r * weight;
+            }
+        }
+        let sum: f32 = rating.iter().sum();
+        if sum <= 0.0 {
+            return None;
+        }
+        for value in rating.iter_mut() {
+            *value /= sum;
+        }
+        Some(rating)
+    }
+}
+
+/// Rates publications by averaging the ratings of several models, e.g. trained with
+/// different cutoffs. A model that knows none of an article's keywords is left out of
+/// that article's average, and so is one with fewer of them than `min_relevant_keywords`.
+pub struct Ensemble {
+    models: Vec<RatingModel>,
+    /// Weighs the keywords of an article, see `RatingModel::rate`.
+    config: AnalyzerConfig,
+}
+
+impl Ensemble {
+    pub fn new(models: Vec<RatingModel>, config: AnalyzerConfig) -> Ensemble {
+        Ensemble { models, config }
+    }
+
+    /// Loads one model per rating database in `paths`, see `RatingModel::load_with`, with
+    /// the `hallmark_mismatch` of `config`. Fails unless all models were trained with the
+    /// same hallmarks.
+    pub fn load(paths: &[String], config: &AnalyzerConfig) -> Result<Ensemble, InputError> {
+        let mut models: Vec<RatingModel> = vec![];
+        for path in paths.iter() {
+            let input_error = |source| InputError::Io {
+                file: path.clone(),
+                source,
+            };
+            let model =
+                RatingModel::load_with(path, config.hallmark_mismatch).map_err(input_error)?;
+            if let Some(first) = models.first() {
+                if first.hallmarks != model.hallmarks {
+                    return Err(input_error(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "the model was trained with other hallmarks than {}",
+                            paths[0]
+                        ),
+                    )));
+                }
+            }
+            models.push(model);
+        }
+        Ok(Ensemble::new(models, config.clone()))
+    }
+
+    pub fn n_models(&self) -> usize {
+        self.models.len()
+    }
+}
+
+impl PublicationRater for Ensemble {
+    fn rate_article_keywords(&self, words: Vec<String>, id: String) -> RatedPublication {
+        let ratings: Vec<Vec<f32>> = self
+            .models
+            .iter()
+            .filter_map(|model| model.rate(&words, &self.config))
+            .collect();
+        let n_hallmarks = self
+            .models
+            .first()
+            .map_or(DEFAULT_HALLMARKS.len(), |model| model.hallmarks.len());
+        let mut rating = vec![0.0; n_hallmarks];
+        for model_rating in ratings.iter() {
+            for (value, r) in rating.iter_mut().zip(model_rating.iter()) {
+                *value += r / ratings.len() as f32;
+            }
+        }
+        RatedPublication {
+            i: id,
+            r: rating,
+            c: None,
+        }
+    }
+
+    fn has_enough_keywords(&self, words: &[String]) -> bool {
+        let minimum = self.config.min_relevant_keywords;
+        minimum == 0
+            || self
+                .models
+                .iter()
+                .any(|model| model.n_relevant_keywords(words) >= minimum)
+    }
+
+    fn hallmark_titles(&self) -> Vec<String> {
+        match self.models.first() {
+            Some(model) => model.hallmarks.clone(),
+            None => default_hallmark_titles(),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+/// Errors of the rating computation that indicate inconsistent analyzer state, e.g. after
+/// loading or merging shards, unreadable input, or a cancelled run.
+#[derive(Debug, PartialEq)]
+pub enum AnalyzerError {
+    /// The relations matrix cannot be multiplied with the rating vector of a hallmark.
+    DimensionMismatch {
+        hallmark: &'static str,
+        matrix_shape: (usize, usize),
+        vector_dim: usize,
+    },
+    /// The co-occurrence counts of a keyword pair differ between both directions.
+    AsymmetricRelations {
+        keyword: String,
+        partner: String,
+        value: f32,
+        transposed: f32,
+    },
+    /// The cutoffs purged every keyword candidate, so there is nothing to rate. Candidates
+    /// are kept if found in more than `lower_bound` and fewer than `upper_bound` abstracts;
+    /// the candidates were found in `min_count` to `max_count` abstracts.
+    EmptyVocabulary {
+        n_candidates: usize,
+        lower_bound: f32,
+        upper_bound: f32,
+        min_count: usize,
+        max_count: usize,
+    },
+    /// Keyword or publication ratings are NaN or infinite under
+    /// `NonFiniteHandling::Fail`. `n_keyword_values` hallmark ratings of keywords are affected
+    /// and `n_publications` publications, which were left out of the article database. The
+    /// first affected keyword rating, if any, is `(keyword, hallmark, value)`.
+    NonFiniteRatings {
+        n_keyword_values: usize,
+        n_publications: usize,
+        first_keyword_value: Option<(String, &'static str, f32)>,
+    },
+    /// The run was stopped through the cancellation flag before all phases finished.
+    Cancelled,
+    /// An input file of articles, a shard or a rating database of the ensemble could not be
+    /// read.
+    Input(InputError),
+}
+
+impl fmt::Display for AnalyzerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalyzerError::DimensionMismatch {
+                hallmark,
+                matrix_shape,
+                vector_dim,
+            } => write!(
+                f,
+                "relations matrix of shape {}x{} does not match the {} keyword ratings of hallmark '{}'",
+                matrix_shape.0, matrix_shape.1, vector_dim, hallmark
+            ),
+            AnalyzerError::AsymmetricRelations {
+                keyword,
+                partner,
+                value,
+                transposed,
+            } => write!(
+                f,
+                "relations matrix is not symmetric: ('{}', '{}') is {} but ('{}', '{}') is {}",
+                keyword, partner, value, partner, keyword, transposed
+            ),
+            AnalyzerError::EmptyVocabulary { n_candidates: 0, .. } => {
+                write!(f, "no keyword candidates were found in the input")
+            }
+            AnalyzerError::EmptyVocabulary {
+                n_candidates,
+                lower_bound,
+                upper_bound,
+                min_count,
+                max_count,
+            } => write!(
+                f,
+                "the cutoffs purged all {} keyword candidates: they were found in {} to {} abstracts, but only those found in more than {} and fewer than {} are kept; lower `lower_cutoff` or raise `upper_cutoff`",
+                n_candidates, min_count, max_count, lower_bound, upper_bound
+            ),
+            AnalyzerError::NonFiniteRatings {
+                first_keyword_value: Some((keyword, hallmark, value)),
+                n_keyword_values,
+                ..
+            } => write!(
+                f,
+                "{} keyword ratings are NaN or infinite, e.g. {} for keyword '{}' and hallmark '{}'",
+                n_keyword_values, value, keyword, hallmark
+            ),
+            AnalyzerError::NonFiniteRatings { n_publications, .. } => write!(
+                f,
+                "the ratings of {} publications are NaN or infinite",
+                n_publications
+            ),
+            AnalyzerError::Cancelled => write!(f, "the run was cancelled"),
+            AnalyzerError::Input(e) => write!(f, "could not read the input: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnalyzerError::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for AnalyzerError {
+    fn from(e: InputError) -> Self {
+        AnalyzerError::Input(e)
+    }
+}
+
+/// Failures to read an input file: of articles, or a shard or rating database.
+#[derive(Debug)]
+pub enum InputError {
+    Io {
+        file: String,
+        source: std::io::Error,
+    },
+    /// The file is no valid JSON array of articles. `article` is the index of the article
+    /// that failed to parse, or `None` if the error lies outside the array elements.
+    Parse {
+        file: String,
+        article: Option<usize>,
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io { file, source } => write!(f, "file {}: {}", file, source),
+            InputError::Parse {
+                file,
+                article: Some(article),
+                source,
+            } => write!(f, "file {}, article {}: {}", file, article, source),
+            InputError::Parse {
+                file,
+                article: None,
+                source,
+            } => write!(f, "file {}: {}", file, source),
+        }
+    }
+}
+
+/// Input errors are equal if they describe the same failure of the same file, since the
+/// underlying errors cannot be compared.
+impl PartialEq for InputError {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { source, .. } => Some(source),
+            InputError::Parse { source, .. } => Some(source),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+pub mod analyzer;
+pub mod analyzer_data;
+pub mod article;
+pub mod config;
+pub mod ensemble;
+pub mod error;
+mod hallmarks;
+pub mod loaders;
+pub mod output;
+pub mod publication_report;
+pub mod rating_diff;
+pub mod rating_validation;
+pub mod summary;
+pub mod synthetic;
+pub mod tokenizer;
+
+pub use hallmarks::{
+    default_hallmark_titles, hallmark_at, hallmark_by_title, hallmark_index, DEFAULT_HALLMARKS,
+};
@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Reads a JSON object mapping hallmark titles to lists of seed keywords, e.g.
+/// `{"Sustained angiogenesis": ["angiogenesis", "vegf"]}`. Keywords are lowercased to
+/// match the vocabulary.
+pub fn load_seed_keywords(path: &str) -> std::io::Result<HashMap<String, Vec<String>>> {
+    let file_contents = fs::read_to_string(path)?;
+    let seeds: HashMap<String, Vec<String>> = serde_json::from_str(&file_contents)?;
+    Ok(seeds
+        .into_iter()
+        .map(|(title, keywords)| {
+            let keywords = keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            (title, keywords)
+        })
+        .collect())
+}
+
+/// Reads a JSON object mapping description phrases to the keywords they seed, e.g.
+/// `{"cell death": ["apoptosis"]}`. Phrases and keywords are lowercased.
+pub fn load_seed_synonyms(path: &str) -> std::io::Result<HashMap<String, Vec<String>>> {
+    Ok(load_seed_keywords(path)?
+        .into_iter()
+        .map(|(phrase, keywords)| (phrase.trim().to_lowercase(), keywords))
+        .filter(|(phrase, _)| !phrase.is_empty())
+        .collect())
+}
+
+/// Reads a plain text file with one keyword per line. Empty lines and lines starting with
+/// `#` are ignored, keywords are lowercased to match the vocabulary.
+pub fn load_keyword_list(path: &str) -> std::io::Result<HashSet<String>> {
+    let file_contents = fs::read_to_string(path)?;
+    Ok(file_contents
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.to_lowercase())
+        .collect())
+}
+
+/// Reads a JSON object mapping aliases to their canonical token, e.g.
+/// `{"neoplasm": "tumor", "tumour": "tumor"}`. Both sides are lowercased.
+pub fn load_synonyms(path: &str) -> std::io::Result<HashMap<String, String>> {
+    let file_contents = fs::read_to_string(path)?;
+    let synonyms: HashMap<String, String> = serde_json::from_str(&file_contents)?;
+    Ok(synonyms
+        .into_iter()
+        .map(|(alias, canonical)| (alias.trim().to_lowercase(), canonical.trim().to_lowercase()))
+        .filter(|(alias, canonical)| !alias.is_empty() && !canonical.is_empty())
+        .collect())
+}
+
+/// Reads a JSON object mapping keywords to their inverse document frequency, e.g.
+/// `{"apoptosis": 3.2}`. Keywords are lowercased to match the vocabulary.
+pub fn load_idf_table(path: &str) -> std::io::Result<HashMap<String, f32>> {
+    let file_contents = fs::read_to_string(path)?;
+    let table: HashMap<String, f32> = serde_json::from_str(&file_contents)?;
+    Ok(table
+        .into_iter()
+        .map(|(keyword, idf)| (keyword.trim().to_lowercase(), idf))
+        .collect())
+}
+
+/// Reads a lemma dictionary, a plain text file with a surface form and its lemma per line
+/// separated by whitespace, e.g. `cells cell`. Empty lines and lines starting with `#` are
+/// ignored, both columns are lowercased.
+pub fn load_lemmas(path: &str) -> std::io::Result<HashMap<String, String>> {
+    let file_contents = fs::read_to_string(path)?;
+    let mut ret = HashMap::new();
+    for line in file_contents.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [form, lemma] => {
+                ret.insert(form.to_lowercase(), lemma.to_lowercase());
+            }
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("'{}' is no surface form followed by its lemma", line),
+                ))
+            }
+        }
+    }
+    Ok(ret)
+}
+
+/// Reads a JSON object mapping single characters to their replacement, e.g.
+/// `{"β": "beta"}`.
+pub fn load_transliterations(path: &str) -> std::io::Result<HashMap<char, String>> {
+    let file_contents = fs::read_to_string(path)?;
+    let table: HashMap<String, String> = serde_json::from_str(&file_contents)?;
+    let mut ret = HashMap::new();
+    for (from, to) in table {
+        let mut chars = from.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                ret.insert(c, to);
+            }
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("'{}' is not a single character", from),
+                ))
+            }
+        }
+    }
+    Ok(ret)
+}
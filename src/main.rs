@@ -1,114 +1,287 @@
-use analyzer_data::Hallmark;
-
-mod analyzer;
-mod analyzer_data;
-mod article;
+use hcse_analyzer::analyzer::{Analyzer, ArticleDatabase};
+use hcse_analyzer::config::{AnalyzerConfig, GREEK_LETTERS, STATISTICAL_PATTERNS};
+use hcse_analyzer::ensemble::RatingModel;
+use hcse_analyzer::loaders;
+use hcse_analyzer::publication_report::{PublicationReport, ReportFormat};
+use hcse_analyzer::rating_diff::RatingDiff;
+use hcse_analyzer::rating_validation::ValidationReport;
+use hcse_analyzer::synthetic::SyntheticCorpus;
+use hcse_analyzer::{hallmark_index, DEFAULT_HALLMARKS};
 
 fn main() {
-    let mut analyzer = analyzer::Analyzer::new(0.05, 0.25);
-    analyzer.run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(|a| a.as_str()) == Some("diff") {
+        diff_rating_databases(&args[1..]);
+        return;
+    }
+    if args.first().map(|a| a.as_str()) == Some("validate") {
+        validate_rating_database(&args[1..]);
+        return;
+    }
+    if args.first().map(|a| a.as_str()) == Some("report") {
+        write_publication_report(&args[1..]);
+        return;
+    }
+    if args.first().map(|a| a.as_str()) == Some("synthetic") {
+        write_synthetic_corpus(&args[1..]);
+        return;
+    }
+    let config = parse_args(args);
+    let mut analyzer = Analyzer::with_config(config);
+    if let Err(e) = analyzer.run() {
+        exit_with_error(&e.to_string());
+    }
+}
+
+fn diff_rating_databases(args: &[String]) {
+    let [old, new] = args else {
+        exit_with_error("Usage: hcse_analyzer diff <old rating database> <new rating database>");
+    };
+    let old = load_file("diff", Some(old.clone()), RatingModel::load);
+    let new = load_file("diff", Some(new.clone()), RatingModel::load);
+    RatingDiff::between(&old, &new).print();
+}
+
+fn validate_rating_database(args: &[String]) {
+    let [path] = args else {
+        exit_with_error("Usage: hcse_analyzer validate <rating database>");
+    };
+    let report = load_file("validate", Some(path.clone()), ValidationReport::of_file);
+    report.print();
+    if !report.is_valid() {
+        std::process::exit(1);
+    }
+}
+
+fn write_publication_report(args: &[String]) {
+    let (path, format) = match args {
+        [path] => (path, None),
+        [path, format] => (path, Some(format.clone())),
+        _ => exit_with_error("Usage: hcse_analyzer report <article database> [markdown|html]"),
+    };
+    let database = load_file("report", Some(path.clone()), ArticleDatabase::load);
+    let format = match format {
+        Some(format) => parse_value("report", Some(format)),
+        None => ReportFormat::default(),
+    };
+    let report = PublicationReport::new(&database.publications, &database.hallmarks)
+        .unwrap_or_else(|e| exit_with_error(&format!("{}: {}", path, e)));
+    print!("{}", report.render(format));
+}
+
+fn write_synthetic_corpus(args: &[String]) {
+    let (dir, seed) = match args {
+        [dir] => (dir, None),
+        [dir, seed] => (dir, Some(seed.clone())),
+        _ => exit_with_error("Usage: hcse_analyzer synthetic <directory> [seed]"),
+    };
+    let mut corpus = SyntheticCorpus::default();
+    if seed.is_some() {
+        corpus.seed = parse_value("synthetic", seed);
+    }
+    std::fs::create_dir_all(dir)
+        .unwrap_or_else(|e| exit_with_error(&format!("Could not create {}: {}", dir, e)));
+    let filenames = corpus
+        .write(std::path::Path::new(dir))
+        .unwrap_or_else(|e| exit_with_error(&format!("Could not write {}: {}", dir, e)));
+    println!(
+        "Wrote {} synthetic input files to {}.",
+        filenames.len(),
+        dir
+    );
+}
+
+fn parse_args(args: Vec<String>) -> AnalyzerConfig {
+    let mut config = AnalyzerConfig::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed-keywords" => {
+                config.seed_keywords = load_file(&arg, args.next(), loaders::load_seed_keywords);
+            }
+            "--seed-synonyms" => {
+                config.seed_synonyms = load_file(&arg, args.next(), loaders::load_seed_synonyms);
+            }
+            "--input" => config.input_files.push(expect_value(&arg, args.next())),
+            "--concatenated-input" => config.concatenated_input = true,
+            "--skip-malformed" => config.skip_malformed_articles = true,
+            "--limit" => config.file_limit = Some(parse_value(&arg, args.next())),
+            "--rate-input" => config
+                .rating_input_files
+                .push(expect_value(&arg, args.next())),
+            "--input-template" => {
+                config.input_file_template = expect_value(&arg, args.next());
+            }
+            "--first-index" => {
+                config.first_input_index = parse_value(&arg, args.next());
+            }
+            "--max-vocabulary" => {
+                config.max_vocabulary_size = Some(parse_value(&arg, args.next()));
+            }
+            "--target-vocabulary" => {
+                config.target_vocabulary_size = Some(parse_value(&arg, args.next()));
+            }
+            "--allowlist" => {
+                config.keyword_allowlist = load_file(&arg, args.next(), loaders::load_keyword_list);
+            }
+            "--denylist" => {
+                config.keyword_denylist = load_file(&arg, args.next(), loaders::load_keyword_list);
+            }
+            "--save-shard" => config.shard_output = Some(expect_value(&arg, args.next())),
+            "--merge-shard" => config.shard_inputs.push(expect_value(&arg, args.next())),
+            "--hyphens" => config.tokenizer.hyphen_mode = parse_value(&arg, args.next()),
+            "--synonyms" => {
+                config.tokenizer.synonyms = load_file(&arg, args.next(), loaders::load_synonyms);
+            }
+            "--lemmas" => {
+                config.tokenizer.lemmas = load_file(&arg, args.next(), loaders::load_lemmas);
+            }
+            "--unit-words" => config.tokenizer.unit_words = true,
+            "--greek" => config.tokenizer.transliterations.extend(
+                GREEK_LETTERS
+                    .iter()
+                    .map(|(letter, latin)| (*letter, latin.to_string())),
+            ),
+            "--transliterate" => config.tokenizer.transliterations.extend(load_file(
+                &arg,
+                args.next(),
+                loaders::load_transliterations,
+            )),
+            "--protect" => {
+                let pattern = expect_value(&arg, args.next());
+                if regex::Regex::new(&pattern).is_err() {
+                    exit_with_error(&format!("Invalid pattern for {}: {}", arg, pattern));
+                }
+                config.tokenizer.protected_patterns.push(pattern);
+            }
+            "--protect-statistics" => config
+                .tokenizer
+                .protected_patterns
+                .extend(STATISTICAL_PATTERNS.iter().map(|p| p.to_string())),
+            "--drop-protected" => config.tokenizer.drop_protected = true,
+            "--quiet" => config.quiet = true,
+            "--article-progress" => config.article_progress = true,
+            "--cache-tokens" => config.cache_tokens = true,
+            "--single-pass" => config.single_pass = true,
+            "--cooccurrence-tf" => {
+                config.cooccurrence_weighting = parse_value(&arg, args.next());
+            }
+            "--cooccurrence-pairing" => {
+                config.cooccurrence_pairing = parse_value(&arg, args.next());
+            }
+            "--diagonal" => config.diagonal_count = parse_value(&arg, args.next()),
+            "--row-normalization" => config.row_normalization = parse_value(&arg, args.next()),
+            "--exclude-self-pairs" => config.exclude_self_pairs = true,
+            "--min-abstract-length" => {
+                config.min_abstract_length = parse_value(&arg, args.next());
+            }
+            "--min-relevant-keywords" => {
+                config.min_relevant_keywords = parse_value(&arg, args.next());
+            }
+            "--propagation" => config.propagation = parse_value(&arg, args.next()),
+            "--exclude-self-loops" => config.exclude_self_loops = true,
+            "--idf" => config.idf_weighting = true,
+            "--idf-file" => {
+                config.idf_table = Some(load_file(&arg, args.next(), loaders::load_idf_table));
+                config.idf_weighting = true;
+            }
+            "--default-idf" => config.default_idf = parse_value(&arg, args.next()),
+            "--rating-output" => config.rating_output_path = expect_value(&arg, args.next()),
+            "--min-output-rating" => {
+                config.min_output_rating = Some(parse_value(&arg, args.next()));
+            }
+            "--output-keywords" => {
+                let pattern = expect_value(&arg, args.next());
+                if regex::Regex::new(&pattern).is_err() {
+                    exit_with_error(&format!("Invalid pattern for {}: {}", arg, pattern));
+                }
+                config.output_keyword_pattern = Some(pattern);
+            }
+            "--article-output" => config.article_output_path = expect_value(&arg, args.next()),
+            "--ensemble" => config.ensemble_models.push(expect_value(&arg, args.next())),
+            "--hallmark-mismatch" => config.hallmark_mismatch = parse_value(&arg, args.next()),
+            "--candidate-output" => {
+                config.candidate_output_path = Some(expect_value(&arg, args.next()));
+            }
+            "--keyword-index-output" => {
+                config.keyword_index_output_path = Some(expect_value(&arg, args.next()));
+            }
+            "--vocabulary-output" => {
+                config.vocabulary_output_path = Some(expect_value(&arg, args.next()));
+            }
+            "--matrix-stats-output" => {
+                config.matrix_stats_output_path = Some(expect_value(&arg, args.next()));
+            }
+            "--seed-rating-output" => {
+                config.seed_rating_output_path = Some(expect_value(&arg, args.next()));
+            }
+            "--validate" => config.validate_ratings = true,
+            "--non-finite" => config.non_finite_ratings = parse_value(&arg, args.next()),
+            "--tf" => config.tf_weighting = parse_value(&arg, args.next()),
+            "--length-normalization" => config.length_normalization = true,
+            "--confidence" => config.publication_confidence = true,
+            "--log-empty-abstracts" => config.log_empty_abstracts = true,
+            "--hallmark-correlations" => config.hallmark_correlations = true,
+            "--shared-keywords" => {
+                let a = parse_hallmark_index(&arg, args.next());
+                let b = parse_hallmark_index(&arg, args.next());
+                config.shared_keywords = Some((a, b));
+            }
+            "--top-keywords" => config.top_keywords = parse_value(&arg, args.next()),
+            "--variant-report" => {
+                config.variant_report = Some(parse_value(&arg, args.next()));
+            }
+            "--threads" => config.threads = Some(parse_value(&arg, args.next())),
+            "--stream-output" => config.stream_article_output = true,
+            _ => exit_with_error(&format!("Unknown argument: {}", arg)),
+        }
+    }
+    if !config.ensemble_models.is_empty() {
+        if config.idf_weighting && config.idf_table.is_none() {
+            exit_with_error("--idf without --idf-file is not supported with --ensemble");
+        }
+        if config.min_output_rating.is_some() {
+            exit_with_error("--min-output-rating has no effect with --ensemble");
+        }
+    }
+    config
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> String {
+    value.unwrap_or_else(|| exit_with_error(&format!("Missing value for {}", flag)))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    let value = expect_value(flag, value);
+    value
+        .parse()
+        .unwrap_or_else(|_| exit_with_error(&format!("Invalid value for {}: {}", flag, value)))
 }
 
-pub const DEFAULT_HALLMARKS: [Hallmark; 10] = [
-    Hallmark {
-        title: "Self-sufficincy in growth signals" ,
-        description: "Normal cells require mitogenic growth signals before they can move from a quiescent state into an active proliferative state. These signals are transmitted into the cell by transmembrane receptors that bind distinctive classes of signaling molecules: diffusible growth factors, extracellular matrix components, and cell-to-cell adhesion/interaction molecules. To our knowledge, no type of normal cell can proliferate in the absence of such stimulatory signals. Many of the oncogenes in the cancer catalog act by mimicking normal growth signaling in one way or another. Dependence on growth signaling is apparent when propagating normal cells in culture, which typically proliferate only when supplied with appropriate diffusible mitogenic factors and a proper substratum for their integrins. Such behavior contrasts strongly with that of tumor cells, which invariably show a greatly reduced dependence on exogenous growth stimulation. The conclusion is that tumor cells generate many of their own growth signals, thereby reducing their dependence on stimulation from their normal tissue microenvironment. This liberation from dependence on exogenously derived signals disrupts a critically important homeostatic mechanism that normally operates to ensure a proper behavior of the various cell types within a tissue. Acquired GS autonomy was the first of the six capabilities to be clearly defined by cancer researchers, in large part because of the prevalence of dominant oncogenes that have been found to modulate it. Three common molecular strategies for achieving autonomy are evident, involving alteration of extracellular growth signals, of transcellular transducers of those signals, or of intracellular circuits that translate those signals into action. While most soluble mitogenic growth factors are made by one cell type in order to stimulate proliferation of another the process of heterotypic signaling many cancer cells acquire the ability to synthesize GFs to which they are responsive, creating a positive feedback signaling loop often termed autocrine stimulation. Clearly, the manufacture of a GF by a cancer cell obviates dependence on GFs from other cells within the tissue. The production of PDGF and by glioblastomas and sarcomas, respectively, are two illustrative examples. The cell surface receptors that transduce growth-stimulatory signals into the cell interior are themselves targets of deregulation during tumor pathogenesis. GF receptors, often carrying tyrosine kinase activities in their cytoplasmic domains, are overexpressed in many cancers. Receptor overexpression may enable the cancer cell to become hyperresponsive to ambient levels of GF that normally would not trigger proliferation. For example, the epidermal receptor is upregulated in stomach, brain, and breast tumors, while the HER2/neu receptor is overexpressed in stomach and mammary carcinomas. Additionally, gross overexpression of GF receptors can elicit ligand-independent signaling. Ligand-independent signaling can also be achieved through structural alteration of receptors; for example, truncated versions of the EGF receptor lacking much of its cytoplasmic domain fire constitutively. Cancer cells can also switch the types of extracellular matrix receptors they express, favoring ones that transmit progrowth signals. These bifunctional, heterodimeric cell surface receptors physically link cells to extracellular superstructures known as the extracellular matrix. Successful binding to specific moieties of the ECM enables the integrin receptors to transduce signals into the cytoplasm that influence cell behavior, ranging from quiescence in normal tissue to motility, resistance to apoptosis, and entrance into the active cell cycle. Conversely, the failure of integrins to forge these extracellular links can impair cell motility, induce apoptosis, or cause cell cycle arrest. Both ligand-activated GF receptors and progrowth integrins engaged to extracellular matrix components can activate the SOS-Ras-Raf-MAP kinase pathway. The most complex mechanisms of acquired GS autonomy derive from alterations in components of the downstream cytoplasmic circuitry that receives and processes the signals emitted by ligand-activated GF receptors and integrins. The SOS-Ras-Raf-MAPK cascade plays a central role here. In about of human tumors, Ras proteins are present in structurally altered forms that enable them to release a flux of mitogenic signals into cells, without ongoing stimulation by their normal upstream regulators. We suspect that growth signaling pathways suffer deregulation in all human tumors. Although this point is hard to prove rigorously at present, the clues are abundant. For example, in the best studied of tumors—human colon carcinomas—about half of the tumors bear mutant ras oncogenes. We suggest that the remaining colonic tumors carry defects in other components of the growth signaling pathways that phenocopy ras oncogene activation. The nature of these alternative, growth-stimulating mechanisms remains elusive. Under intensive study for two decades, the wiring diagram of the growth signaling circuitry of the mammalian cell is coming into focus. New downstream effector pathways that radiate from the central SOS-Ras-Raf-MAP kinase mitogenic cascade are being discovered with some regularity. This cascade is also linked via a variety of cross-talking connections with other pathways; these cross connections enable extracellular signals to elicit multiple cell biological effects. For example, the direct interaction of the Ras protein with the survival-promoting PI3 kinase enables growth signals to concurrently evoke survival signals within the cell. While acquisition of growth signaling autonomy by cancer cells is conceptually satisfying, it is also too simplistic. We have traditionally explored tumor growth by focusing our experimental attentions on the genetically deranged cancer cells. It is, however, increasingly apparent that the growth deregulation within a tumor can only be explained once we understand the contributions of the ancillary cells present in a tumor - the apparently normal bystanders such as fibroblasts and endothelial cells- which must play key roles in driving tumor cell proliferation. Within normal tissue, cells are largely instructed to grow by their neighbors or via systemic signals. Cell-to-cell growth signaling is likely to operate in the vast majority of human tumors as well; virtually all are composed of several distinct cell types that appear to communicate via heterotypic signaling",
-    },
-    Hallmark {
-        title: "Evading apoptosis",
-        description: "The ability of tumor cell populations to expand in number is determined not only by the rate of cell proliferation but also by the rate of cell attrition. Programmed cell death—apoptosis—represents a major source of this attrition. The evidence is mounting, principally from studies in mouse models and cultured cells, as well as from descriptive analyses of biopsied stages in human carcinogenesis, that acquired resistance toward apoptosis is a hallmark of most and perhaps all types of cancer.
-Observations accumulated over the past decade indicate that the apoptotic program is present in latent form in virtually all cell types throughout the body. Once triggered by a variety of physiologic signals, this program unfolds in a precisely choreographed series of steps. Cellular membranes are disrupted, the cytoplasmic and nuclear skeletons are broken down, the cytosol is extruded, the chromosomes are degraded, and the nucleus is fragmented, all in a span of 30–120 min. In the end, the shriveled cell corpse is engulfed by nearby cells in a tissue and disappears, typically within 24 hr (99).
-The apoptotic machinery can be broadly divided into two classes of components—sensors and effectors. The sensors are responsible for monitoring the extracellular and intracellular environment for conditions of normality or abnormality that influence whether a cell should live or die. These signals regulate the second class of components, which function as effectors of apoptotic death. The sentinels include cell surface receptors that bind survival or death factors. Examples of these ligand/receptor pairs include survival signals conveyed by IGF-1/IGF-2 through their receptor, IGF-1R, and by IL-3 and its cognate receptor, IL-3R (63, 12). Death signals are conveyed by the FAS ligand binding the FAS receptor and by TNFα binding TNF-R1 (2). Intracellular sensors monitor the cell's well-being and activate the death pathway in response to detecting abnormalities, including DNA damage, signaling imbalance provoked by oncogene action, survival factor insufficiency, or hypoxia (28). Further, the life of most cells is in part maintained by cell–matrix and cell–cell adherence-based survival signals whose abrogation elicits apoptosis (50, 36). Both soluble and immobilized apoptotic regulatory signals likely reflect the needs of tissues to maintain their constituent cells in appropriate architectural configurations.
-Many of the signals that elicit apoptosis converge on the mitochondria, which respond to proapoptotic signals by releasing cytochrome C, a potent catalyst of apoptosis (37). Members of the Bcl-2 family of proteins, whose members have either proapoptotic (Bax, Bak, Bid, Bim) or antiapoptotic (Bcl-2, Bcl-XL, Bcl-W) function, act in part by governing mitochondrial death signaling through cytochrome C release. The p53 tumor suppressor protein can elicit apoptosis by upregulating expression of proapoptotic Bax in response to sensing DNA damage; Bax in turn stimulates mitochondria to release cytochrome C.
-The ultimate effectors of apoptosis include an array of intracellular proteases termed caspases (89). Two “gatekeeper” caspases, −8 and −9, are activated by death receptors such as FAS or by the cytochrome C released from mitochondria, respectively. These proximal caspases trigger the activation of a dozen or more effector caspases that execute the death program, through selective destruction of subcellular structures and organelles, and of the genome.
-The possibility that apoptosis serves as a barrier to cancer was first raised in 1972, when Kerr, Wyllie, and Currie described massive apoptosis in the cells populating rapidly growing, hormone-dependent tumors following hormone withdrawal (55). The discovery of the bcl-2 oncogene by its upregulation via chromosomal translocation in follicular lymphoma (reviewed in 59) and its recognition as having antiapoptotic activity (91) opened up the investigation of apoptosis in cancer at the molecular level. When coexpressed with a myc oncogene in transgenic mice, the bcl-2 gene was able to promote formation of B cell lymphomas by enhancing lymphocyte survival, not by further stimulating their myc-induced proliferation (87); further, 50% of the infrequent lymphomas arising in bcl-2 single transgenic transgenic mice had somatic translocations activating c-myc, confirming a selective pressure during lymphomagenesis to upregulate both Bcl-2 and c-Myc (67).
-Further insight into the myc-bcl-2 interaction emerged later from studying the effects of a myc oncogene on fibroblasts cultured in low serum. Widespread apoptosis was induced in myc-expressing cells lacking serum; the consequent apoptosis could be abrogated by exogenous survival factors (e.g., IGF-1), by forced overexpression of Bcl-2 or the related Bcl-XL protein, or by disruption of the FAS death signaling circuit (47). Collectively, the data indicate that a cell's apoptotic program can be triggered by an overexpressed oncogene. Indeed, elimination of cells bearing activated oncogenes by apoptosis may represent the primary means by which such mutant cells are continually culled from the body's tissues.
-Other examples strengthen the consensus that apoptosis is a major barrier to cancer that must be circumvented. Thus, in transgenic mice where the pRb tumor suppressor was functionally inactivated in the choroid plexus, slowly growing microscopic tumors arose, exhibiting high apoptotic rates; the additional inactivation of the p53 tumor suppressor protein, a component of the apoptotic signaling circuitry, led to rapidly growing tumors containing low numbers of apoptotic cells (88). The role of extracellular survival factors is illustrated by disease progression in transgenic mice prone to pancreatic islet tumors. If IGF-2 gene expression, which is activated in this tumorigenesis pathway, was abrogated using gene knockout mice, tumor growth and progression were impaired, as evidenced by the appearance of comparatively small, benign tumors showing high rates of apoptosis (17). In these cells, the absence of IGF-2 did not affect cell proliferation rates, clearly identifying it as an antiapoptotic survival factor. Collectively, these observations argue that altering components of the apoptotic machinery can dramatically affect the dynamics of tumor progression, providing a rationale for the inactivation of this machinery during tumor development.
-Resistance to apoptosis can be acquired by cancer cells through a variety of strategies. Surely, the most commonly occurring loss of a proapoptotic regulator through mutation involves the p53 tumor suppressor gene. The resulting functional inactivation of its product, the p53 protein, is seen in greater than 50% of human cancers and results in the removal of a key component of the DNA damage sensor that can induce the apoptotic effector cascade (43). Signals evoked by other abnormalities, including hypoxia and oncogene hyperexpression, are also funneled in part via p53 to the apoptotic machinery; these too are impaired at eliciting apoptosis when p53 function is lost (61). Additionally, the PI3 kinase–AKT/PKB pathway, which transmits antiapoptotic survival signals, is likely involved in mitigating apoptosis in a substantial fraction of human tumors. This survival signaling circuit can be activated by extracellular factors such as IGF-1/2 or IL-3 (28), by intracellular signals emanating from Ras (26), or by loss of the pTEN tumor suppressor, a phospholipid phosphatase that normally attenuates the AKT survival signal (13). Recently, a mechanism for abrogating the FAS death signal has been revealed in a high fraction of lung and colon carcinoma cell lines: a nonsignaling decoy receptor for FAS ligand is upregulated, titrating the death-inducing signal away from the FAS death receptor (74). We expect that virtually all cancer cells harbor alterations that enable evasion of apoptosis.
-It is now possible to lay out a provisional apoptotic signaling circuitry (Figure 2); while incomplete, it is evident that most regulatory and effector components are present in redundant form. This redundancy holds important implications for the development of novel types of antitumor therapy, since tumor cells that have lost proapoptotic components are likely to retain other similar ones. We anticipate that new technologies will be able to display the apoptotic pathways still operative in specific types of cancer cells and that new drugs will enable cross-talk between the still intact components of parallel apoptotic signaling pathways in tumor cells, resulting in restoration of the apoptotic defense mechanism, with substantial therapeutic benefit.",
-    },
-    Hallmark {
-        title: "Insensitivity to anti-growth signals",
-        description: "Within a normal tissue, multiple antiproliferative signals operate to maintain cellular quiescence and tissue homeostasis; these signals include both soluble growth inhibitors and immobilized inhibitors embedded in the extracellular matrix and on the surfaces of nearby cells. These growth-inhibitory signals, like their positively acting counterparts, are received by transmembrane cell surface receptors coupled to intracellular signaling circuits.
-Antigrowth signals can block proliferation by two distinct mechanisms. Cells may be forced out of the active proliferative cycle into the quiescent (G0) state from which they may reemerge on some future occasion when extracellular signals permit. Alternatively, cells may be induced to permanently relinquish their proliferative potential by being induced to enter into postmitotic states, usually associated with acquisition of specific differentiation-associated traits.
-Incipient cancer cells must evade these antiproliferative signals if they are to prosper. Much of the circuitry that enables normal cells to respond to antigrowth signals is associated with the cell cycle clock, specifically the components governing the transit of the cell through the G1 phase of its growth cycle. Cells monitor their external environment during this period and, on the basis of sensed signals, decide whether to proliferate, to be quiescent, or to enter into a postmitotic state. At the molecular level, many and perhaps all antiproliferative signals are funneled through the retinoblastoma protein (pRb) and its two relatives, p107 and p130. When in a hypophosphorylated state, pRb blocks proliferation by sequestering and altering the function of E2F transcription factors that control the expression of banks of genes essential for progression from G1 into S phase (95).
-Disruption of the pRb pathway liberates E2Fs and thus allows cell proliferation, rendering cells insensitive to antigrowth factors that normally operate along this pathway to block advance through the G1 phase of the cell cycle. The effects of the soluble signaling molecule TGFβ are the best documented, but we envision other antigrowth factors will be found to signal through this pathway as well. TGFβ acts in a number of ways, most still elusive, to prevent the phosphorylation that inactivates pRb; in this fashion, TGFβ blocks advance through G1. In some cell types, TGFβ suppresses expression of the c-myc gene, which regulates the G1 cell cycle machinery in still unknown ways (70). More directly, TGFβ causes synthesis of the p15INK4B and p21 proteins, which block the cyclin:CDK complexes responsible for pRb phosphorylation (42, 24).
-The pRb signaling circuit, as governed by TGFβ and other extrinsic factors, can be disrupted in a variety of ways in different types of human tumors (34). Some lose TGFβ responsiveness through downregulation of their TGFβ receptors, while others display mutant, dysfunctional receptors (34, 65). The cytoplasmic Smad4 protein, which transduces signals from ligand-activated TGFβ receptors to downstream targets, may be eliminated through mutation of its encoding gene (78). The locus encoding p15INK4B may be deleted (15). Alternatively, the immediate downstream target of its actions, CDK4, may become unresponsive to the inhibitory actions of p15INK4B because of mutations that create amino acid substitutions in its INK4A/B-interacting domain; the resulting cyclin D:CDK4 complexes are then given a free hand to inactivate pRb by hyperphosphorylation (102). Finally, functional pRb, the end target of this pathway, may be lost through mutation of its gene. Alternatively, in certain DNA virus-induced tumors, notably cervical carcinomas, pRb function is eliminated through sequestration by viral oncoproteins, such as the E7 oncoprotein of human papillomavirus (27). In addition, cancer cells can also turn off expression of integrins and other cell adhesion molecules that send antigrowth signals, favoring instead those that convey progrowth signals; these adherence-based antigrowth signals likely impinge on the pRb circuit as well. The bottom line is that the antigrowth circuit converging onto Rb and the cell division cycle is, one way or another, disrupted in a majority of human cancers, defining the concept and a purpose of tumor suppressor loss in cancer.
-Cell proliferation depends on more than an avoidance of cytostatic antigrowth signals. Our tissues also constrain cell multiplication by instructing cells to enter irreversibly into postmitotic, differentiated states, using diverse mechanisms that are incompletely understood; it is apparent that tumor cells use various strategies to avoid this terminal differentiation. One strategy for avoiding differentiation directly involves the c-myc oncogene, which encodes a transcription factor. During normal development, the growth-stimulating action of Myc, in association with another factor, Max, can be supplanted by alternative complexes of Max with a group of Mad transcription factors; the Mad–Max complexes elicit differentiation-inducing signals (31). However, overexpression of the c-Myc oncoprotein, as is seen in many tumors, can reverse this process, shifting the balance back to favor Myc–Max complexes, thereby impairing differentiation and promoting growth. During human colon carcinogenesis, inactivation of the APC/β-catenin pathway serves to block the egress of enterocytes in the colonic crypts into a differentiated, postmitotic state (57). Analogously, during the generation of avian erythroblastosis, the erbA oncogene acts to prevent irreversible erythrocyte differentiation (53).
-While the components and interconnections between the various antigrowth and differentiation-inducing signals and the core cell cycle machinery are still being delineated, the existence of an antigrowth signaling circuitry is clear (Figure 2), as is the necessity for its circumvention by developing cancers",
-    },
-    Hallmark {
-        title: "Sustained angiogenesis",
-        description: "The oxygen and nutrients supplied by the vasculature are crucial for cell function and survival, obligating virtually all cells in a tissue to reside within 100 μm of a capillary blood vessel. During organogenesis, this closeness is ensured by coordinated growth of vessels and parenchyma. Once a tissue is formed, the growth of new blood vessels—the process of angiogenesis—is transitory and carefully regulated. Because of this dependence on nearby capillaries, it would seem plausible that proliferating cells within a tissue would have an intrinsic ability to encourage blood vessel growth. But the evidence is otherwise. The cells within aberrant proliferative lesions initially lack angiogenic ability, curtailing their capability for expansion. In order to progress to a larger size, incipient neoplasias must develop angiogenic ability (8, 41, 32).
-Counterbalancing positive and negative signals encourage or block angiogenesis. One class of these signals is conveyed by soluble factors and their receptors, the latter displayed on the surface of endothelial cells; integrins and adhesion molecules mediating cell–matrix and cell–cell association also play critical roles. The angiogenesis-initiating signals are exemplified by vascular endothelial growth factor (VEGF) and acidic and basic fibroblast growth factors (FGF1/2). Each binds to transmembrane tyrosine kinase receptors displayed by endothelial cells (29, 93). A prototypical angiogenesis inhibitor is thrombospondin-1, which binds to CD36, a transmembrane receptor on endothelial cells coupled to intracellular Src-like tyrosine kinases (11). There are currently more than two dozen angiogenic inducer factors known and a similar number of endogenous inhibitor proteins.
-Integrin signaling also contributes to this regulatory balance. Quiescent vessels express one class of integrins, whereas sprouting capillaries express another. Interference with signaling from the latter class of integrins can inhibit angiogenesis (90, 36), underscoring the important contribution of cell adhesion to the angiogenic program (49). Extracellular proteases are physically and functionally connected with proangiogenic integrins, and both help dictate the invasive capability of angiogenic endothelial cells (86).
-Experimental evidence for the importance of inducing and sustaining angiogenesis in tumors is both extensive and compelling (8, 41, 32). The story begins almost 30 years ago with Folkman and colleagues, who used in vivo bioassays to demonstrate the necessity of angiogenesis for explosive growth of tumor explants (reviewed in 32). Molecular proof of principle came, for example, when anti-VEGF antibodies proved able to impair neovascularization and growth of subcutaneous tumors in mice (56), as did a dominant-interfering version of the VEGF receptor 2 (flk-1) (69); both results have motivated the development of specific VEGF/VEGF-R inhibitors now in late stage clinical trials.
-The essential role of angiogenesis is further supported by the ability of an increasing catalog of antiangiogenic substances to impair the growth of tumor cells inoculated subcutaneously in mice (32). Tumors arising in cancer-prone transgenic mice are similarly susceptible to angiogenic inhibitors (5).
-The ability to induce and sustain angiogenesis seems to be acquired in a discrete step (or steps) during tumor development, via an “angiogenic switch” from vascular quiescence. When three transgenic mouse models were analyzed throughout multistep tumorigenesis, in each case angiogenesis was found to be activated in midstage lesions, prior to the appearance of full-blown tumors. Similarly, angiogenesis can be discerned in premalignant lesions of the human cervix, breast, and skin (melanocytes) (41); we expect that induction of angiogenesis will prove to be an early to midstage event in many human cancers. These observations, taken together with the effects of angiogenesis inhibitors, indicate that neovascularization is a prerequisite to the rapid clonal expansion associated with the formation of macroscopic tumors.
-Tumors appear to activate the angiogenic switch by changing the balance of angiogenesis inducers and countervailing inhibitors (41). One common strategy for shifting the balance involves altered gene transcription. Many tumors evidence increased expression of VEGF and/or FGFs compared to their normal tissue counterparts. In others, expression of endogenous inhibitors such as thrombospondin-1 or β-interferon is downregulated. Moreover, both transitions may occur, and indeed be linked, in some tumors (82, 94).
-The mechanisms underlying shifts in the balances between angiogenic regulators remain incompletely understood. In one well-documented example, the inhibitor thrombospondin-1 has been found to positively regulated by the p53 tumor suppressor protein in some cell types. Consequently, loss of p53 function, which occurs in most human tumors, can cause thrombospondin-1 levels to fall, liberating endothelial cells from its inhibitory effects (23). The VEGF gene is also under complex transcriptional control. For example, activation of the ras oncogene or loss of the VHL tumor suppressor gene in certain cell types causes upregulation of VEGF expression (75, 66).
-Another dimension of regulation is emerging in the form of proteases, which can control the bioavailability of angiogenic activators and inhibitors. Thus, a variety of proteases can release bFGF stored in the ECM (97), whereas plasmin, a proangiogenic component of the clotting system, can cleave itself into an angiogenesis inhibitor form called angiostatin (35). The coordinated expression of pro- and antiangiogenic signaling molecules, and their modulation by proteolysis, appear to reflect the complex homeostatic regulation of normal tissue angiogenesis and of vascular integrity.
-As is already apparent, tumor angiogenesis offers a uniquely attractive therapeutic target, indeed one that is shared in common by most and perhaps all types of human tumors. The next decade will produce a catalog of the angiogenic regulatory molecules expressed by different types of tumors, and in many cases, by their progenitor stages. Use of increasingly sophisticated mouse models will make it possible to assign specific roles to each of these regulators and to discern the molecular mechanisms that govern their production and activity. Already available evidence indicates that different types of tumor cells use distinct molecular strategies to activate the angiogenic switch. This raises the question of whether a single antiangiogenic therapeutic will suffice to treat all tumor types, or whether an ensemble of such therapeutics will need to be developed, each responding to a distinct program of angiogenesis that has been developed by a specific class of human tumors.",
-    },
-    Hallmark {
-        title: "Limitless replicative potential",
-        description: "Three acquired capabilities—growth signal autonomy, insensitivity to antigrowth signals, and resistance to apoptosis—all lead to an uncoupling of a cell's growth program from signals in its environment. In principle, the resulting deregulated proliferation program should suffice to enable the generation of the vast cell populations that constitute macroscopic tumors. However, research performed over the past 30 years indicates that this acquired disruption of cell-to-cell signaling, on its own, does not ensure expansive tumor growth. Many and perhaps all types of mammalian cells carry an intrinsic, cell-autonomous program that limits their multiplication. This program appears to operate independently of the cell-to-cell signaling pathways described above. It too must be disrupted in order for a clone of cells to expand to a size that constitutes a macroscopic, life-threatening tumor.
-The early work of Hayflick demonstrated that cells in culture have a finite replicative potential (reviewed in 44). Once such cell populations have progressed through a certain number of doublings, they stop growing—a process termed senescence. The senescence of cultured human fibroblasts can be circumvented by disabling their pRb and p53 tumor suppressor proteins, enabling these cells to continue multiplying for additional generations until they enter into a second state termed crisis. The crisis state is characterized by massive cell death, karyotypic disarray associated with end-to-end fusion of chromosomes, and the occasional emergence of a variant (1 in 107) cell that has acquired the ability to multiply without limit, the trait termed immortalization (98).
-Provocatively, most types of tumor cells that are propagated in culture appear to be immortalized, suggesting that limitless replicative potential is a phenotype that was acquired in vivo during tumor progression and was essential for the development of their malignant growth state (44). This result suggests that at some point during the course of multistep tumor progression, evolving premalignant cell populations exhaust their endowment of allowed doublings and can only complete their tumorigenic agenda by breaching the mortality barrier and acquiring unlimited replicative potential.
-Observations of cultured cells indicate that various normal human cell types have the capacity for 60–70 doublings. Taken at face value, these numbers make little sense when attempting to invoke cell mortality as an impediment to cancer formation: 60–70 doublings should enable clones of tumor cells to expand to numbers that vastly exceed the number of cells in the human body. If clues from evaluation of proliferation and apoptotic rates in certain human tumors (99) and transgenic mouse models (88, 80, 4) prove generalizable, the paradox can be resolved: evolving premalignant and malignant cell populations evidence chronic, widespread apoptosis and consequently suffer considerable cell attrition concomitant with cell accumulation. Thus, the number of cells in a tumor greatly underrepresents the cell generations required to produce it, raising the generational limit of normal somatic cells as a barrier to cancer.
-The counting device for cell generations has been discovered over the past decade: the ends of chromosomes, telomeres, which are composed of several thousand repeats of a short 6 bp sequence element. Replicative generations are counted by the 50–100 bp loss of telomeric DNA from the ends of every chromosome during each cell cycle. This progressive shortening has been attributed to the inability of DNA polymerases to completely replicate the 3′ ends of chromosomal DNA during each S phase. The progressive erosion of telomeres through successive cycles of replication eventually causes them to lose their ability to protect the ends of chromosomal DNA. The unprotected chromosomal ends participate in end-to-end chromosomal fusions, yielding the karyotypic disarray associated with crisis and resulting, almost inevitably, in the death of the affected cell (19).
-Telomere maintenance is evident in virtually all types of malignant cells (81); 85%–90% of them succeed in doing so by upregulating expression of the telomerase enzyme, which adds hexanucleotide repeats onto the ends of telomeric DNA (9), while the remainder have invented a way of activating a mechanism, termed ALT, which appears to maintain telomeres through recombination-based interchromosomal exchanges of sequence information (10). By one or the other mechanism, telomeres are maintained at a length above a critical threshold, and this in turn permits unlimited multiplication of descendant cells. Both mechanisms seem to be strongly suppressed in most normal human cells in order to deny them unlimited replicative potential.
-The role of telomerase in immortalizing cells can be demonstrated directly by ectopically expressing the enzyme in cells, where it can convey unlimited replicative potential onto a variety of normal early passage, presenescent cells in vitro (7, 92). Further, late passage cells poised to enter crisis continue to proliferate without giving any evidence of crisis when supplied with this enzyme (20, 40, 101). Additional clues into the importance of telomere maintenance for cancer comes from analysis of mice lacking telomerase function. For example, mice carrying a homozygous knockout of the cell cycle inhibitor p16INK4A are tumor prone, particularly when exposed to carcinogens; the tumors that arise show comparatively elevated telomerase activity. When carcinogens were applied to p16INK4A-null mice that also lacked telomerase, tumor incidence was reduced, concomitant with substantial telomere shortening and karyotypic disarray in those tumors that did appear (38).
-While telomere maintenance is clearly a key component of the capability for unlimited replication, we remain uncertain about another one, the circumvention of cellular senescence. The phenomenon of senescence was originally observed as a delayed response of primary cells to extended propagation in vitro and has thus been associated with mechanisms of divisional counting (44). More recently, the senescent state has been observed to be inducible in certain cultured cells in response to high level expression of genes such as the activated ras oncogene (79).
-The above-cited observations might argue that senescence, much like apoptosis, reflects a protective mechanism that can be activated by shortened telomeres or conflicting growth signals that forces aberrant cells irreversibly into a G0-like state, thereby rendering them incapable of further proliferation. If so, circumvention of senescence in vivo may indeed represent an essential step in tumor progression that is required for the subsequent approach to and breaching of the crisis barrier. But we consider an alternative model equally plausible: senescence could be an artifact of cell culture that does not reflect a phenotype of cells within living tissues and does not represent an impediment to tumor progression in vivo. Resolution of this quandary will be critical to completely understand the acquisition of limitless replicative potential.",
-    },
-    Hallmark {
-        title: "Tissue invasion and metastasis",
-        description: "Sooner or later during the development of most types of human cancer, primary tumor masses spawn pioneer cells that move out, invade adjacent tissues, and thence travel to distant sites where they may succeed in founding new colonies. These distant settlements of tumor cells—metastases—are the cause of 90% of human cancer deaths (85). The capability for invasion and metastasis enables cancer cells to escape the primary tumor mass and colonize new terrain in the body where, at least initially, nutrients and space are not limiting. The newly formed metastases arise as amalgams of cancer cells and normal supporting cells conscripted from the host tissue. Like the formation of the primary tumor mass, successful invasion and metastasis depend upon all of the other five acquired hallmark capabilities. But what additional cellular changes enable the acquisition of these final capabilities during tumorigenesis?
-Invasion and metastasis are exceedingly complex processes, and their genetic and biochemical determinants remain incompletely understood. At the mechanistic level, they are closely allied processes, which justifies their association with one another as one general capability of cancer cells. Both utilize similar operational strategies, involving changes in the physical coupling of cells to their microenvironment and activation of extracellular proteases.
-Several classes of proteins involved in the tethering of cells to their surroundings in a tissue are altered in cells possessing invasive or metastatic capabilities. The affected proteins include cell–cell adhesion molecules (CAMs)—notably members of the immunoglobulin and calcium-dependent cadherin families, both of which mediate cell-to-cell interactions—and integrins, which link cells to extracellular matrix substrates. Notably, all of these “adherence” interactions convey regulatory signals to the cell (1). The most widely observed alteration in cell-to-environment interactions in cancer involves E-cadherin, a homotypic cell-to-cell interaction molecule ubiquitously expressed on epithelial cells. Coupling between adjacent cells by E-cadherin bridges results in the transmission of antigrowth and other signals via cytoplasmic contacts with β-catenin to intracellular signaling circuits that include the Lef/Tcf transcription factor (16). E-cadherin function is apparently lost in a majority of epithelial cancers, by mechanisms that include mutational inactivation of the E-cadherin or β-catenin genes, transcriptional repression, or proteolysis of the extracellular cadherin domain (16). Forced expression of E-cadherin in cultured cancer cells and in a transgenic mouse model of carcinogenesis impairs invasive and metastatic phenotypes, whereas interference with E-cadherin function enhances both capabilities (16). Thus, E-cadherin serves as a widely acting suppressor of invasion and metastasis by epithelial cancers, and its functional elimination represents a key step in the acquisition of this capability.
-Changes in expression of CAMs in the immunoglobulin superfamily also appear to play critical roles in the processes of invasion and metastasis (52). The clearest case involves N-CAM, which undergoes a switch in expression from a highly adhesive isoform to poorly adhesive (or even repulsive) forms in Wilms' tumor, neuroblastoma, and small cell lung cancer (52, 54) and reduction in overall expression level in invasive pancreatic and colorectal cancers (30). Experiments in transgenic mice support a functional role for the normal adhesive form of N-CAM in suppressing metastasis (73).
-Changes in integrin expression are also evident in invasive and metastatic cells. Invading and metastasizing cancer cells experience changing tissue microenvironments during their journeys, which can present novel matrix components. Accordingly, successful colonization of these new sites (both local and distant) demands adaptation, which is achieved through shifts in the spectrum of integrin α or β subunits displayed by the migrating cells. These novel permutations result in different integrin subtypes (of which there are greater than 22) having distinct substrate preferences. Thus, carcinoma cells facilitate invasion by shifting their expression of integrins from those that favor the ECM present in normal epithelium to other integrins (e.g., α3β1 and αVβ3) that preferentially bind the degraded stromal components produced by extracellular proteases (90, 64). Forced expression of integrin subunits in cultured cells can induce or inhibit invasive and metastatic behavior, consistent with a role of these receptors in acting as central determinants of these processes (90).
-Attempts at explaining the cell biological effects of integrins in terms of a small number of mechanistic rules have been confounded by the large number of distinct integrin genes, by the even larger number of heterodimeric receptors resulting from combinatorial expression of various α and β receptor subunits, and by the increasing evidence of complex signals emitted by the cytoplasmic domains of these receptors (1, 36). Still, there is little doubt that these receptors play central roles in the capability for tissue invasion and metastasis.
-The second general parameter of the invasive and metastatic capability involves extracellular proteases (21, 14). Protease genes are upregulated, protease inhibitor genes are downregulated, and inactive zymogen forms of proteases are converted into active enzymes. Matrix-degrading proteases are characteristically associated with the cell surface, by synthesis with a transmembrane domain, binding to specific protease receptors, or association with integrins (96, 86). One imagines that docking of active proteases on the cell surface can facilitate invasion by cancer cells into nearby stroma, across blood vessel walls, and through normal epithelial cell layers. That notion notwithstanding, it is difficult to unambiguously ascribe the functions of particular proteases solely to this capability, given their evident roles in other hallmark capabilities, including angiogenesis (86) and growth signaling (96, 3), which in turn contribute directly or indirectly to the invasive/metastatic capability.
-A further dimension of complexity derives from the multiple cell types involved in protease expression and display. In many types of carcinomas, matrix-degrading proteases are produced not by the epithelial cancer cells but rather by conscripted stromal and inflammatory cells (96); once released by these cells, they may be wielded by the carcinoma cells. For example, certain cancer cells induce urokinase (uPA) expression in cocultured stromal cells, which then binds to the urokinase receptor (uPAR) expressed on the cancer cells (51).
-The activation of extracellular proteases and the altered binding specificities of cadherins, CAMs, and integrins are clearly central to the acquisition of invasiveness and metastatic ability. But the regulatory circuits and molecular mechanisms that govern these shifts remain elusive and, at present, seem to differ from one tissue environment to another. The acquired capability for invasion and metastasis represents the last great frontier for exploratory cancer research. We envision that evolving analytic techniques will soon make it possible to construct comprehensive profiles of the expression and functional activities of proteases, integrins, and CAMs in a wide variety of cancer types, both before and after they acquire invasive and metastatic abilities. The challenge will then be to apply the new molecular insights about tissue invasiveness and metastasis to the development of effective therapeutic strategies.",
-    },
-    Hallmark {
-        title: "Genome instability and mutation",
-        description: "Acquisition of the multiple hallmarks enumerated above depends in large part on a succession of alterations in the genomes of neoplastic cells. Simply depicted, certain mutant genotypes confer selective advantage on subclones of cells, enabling their outgrowth and eventual dominance in a local tissue environment. Accordingly, multistep tumor progression can be portrayed as a succession of clonal expansions, each of which is triggered by the chance acquisition of an enabling mutant genotype. Because heritable phenotypes, e.g., inactivation of tumor suppressor genes, can also be acquired through epigenetic mechanisms such as DNA methylation and histone modifications (Berdasco and Esteller, 2010, Esteller, 2007, Jones and Baylin, 2007), some clonal expansions may well be triggered by nonmutational changes affecting the regulation of gene expression.
-The extraordinary ability of genome maintenance systems to detect and resolve defects in the DNA ensures that rates of spontaneous mutation are usually very low during each cell generation. In the course of acquiring the roster of mutant genes needed to orchestrate tumorigenesis, cancer cells often increase the rates of mutation (Negrini et al., 2010, Salk et al., 2010). This mutability is achieved through increased sensitivity to mutagenic agents, through a breakdown in one or several components of the genomic maintenance machinery, or both. In addition, the accumulation of mutations can be accelerated by compromising the surveillance systems that normally monitor genomic integrity and force genetically damaged cells into either senescence or apoptosis (Jackson and Bartek, 2009, Kastan, 2008, Sigal and Rotter, 2000). The role of TP53 is central here, leading to its being called the “guardian of the genome” (Lane, 1992).
-A diverse array of defects affecting various components of the DNA-maintenance machinery—often referred to as the “caretakers” of the genome (Kinzler and Vogelstein, 1997)—have been documented. The catalog of defects in these caretaker genes includes those whose products are involved in (1) detecting DNA damage and activating the repair machinery, (2) directly repairing damaged DNA, and (3) inactivating or intercepting mutagenic molecules before they have damaged the DNA (Negrini et al., 2010, Ciccia and Elledge, 2010, Jackson and Bartek, 2009, Kastan, 2008, Harper and Elledge, 2007, Friedberg et al., 2006). From a genetic perspective, these caretaker genes behave much like tumor suppressor genes, in that their functions can be lost during the course of tumor progression, with such losses being achieved either through inactivating mutations or via epigenetic repression. Mutant copies of many of these caretaker genes have been introduced into the mouse germline and result, predictably, in increased cancer incidence, supporting their potential involvement in human cancer development (Barnes and Lindahl, 2004).
-In the decade since we first enumerated the cancer hallmarks, another major source of tumor-associated genomic instability has been uncovered: as described earlier, the loss of telomeric DNA in many tumors generates karyotypic instability and associated amplification and deletion of chromosomal segments (Artandi and DePinho, 2010). When viewed in this light, telomerase is more than an enabler of the hallmark capability for unlimited replicative potential and must also be added to the list of critical caretakers responsible for maintaining genome integrity.
-Advances in the molecular-genetic analysis of cancer cell genomes have provided the most compelling demonstrations of function-altering mutations and of ongoing genomic instability during tumor progression. One type of analysis—comparative genomic hybridization (CGH)—documents the gains and losses of gene copy number across the cell genome; in many tumors, the pervasive genomic aberrations revealed by CGH provide clear evidence for loss of control of genome integrity. Importantly, the recurrence of specific aberrations (both amplifications and deletions) at particular sites in the genome indicates that such sites are likely to harbor genes whose alteration favors neoplastic progression (Korkola and Gray, 2010.
-More recently, with the advent of efficient and economical DNA-sequencing technologies, higher-resolution analyses have become possible. Early studies are revealing distinctive patterns of DNA mutations in different tumor types (see http://cancergenome.nih.gov/). In the not-too-distant future, the sequencing of entire cancer cell genomes promises to clarify the prevalence of ostensibly random mutations scattered across cancer cell genomes. Thus, recurring genetic alterations may point to a causal role of particular mutations in tumor pathogenesis.
-Although the specifics of genome alteration vary dramatically between different tumor types, the large number of genome maintenance and repair defects that have already been documented in human tumors, together with abundant evidence of widespread destabilization of gene copy number and nucleotide sequence, persuade us that instability of the genome is inherent to the great majority of human cancer cells. This leads, in turn, to the conclusion that the defects in genome maintenance and repair are selectively advantageous and therefore instrumental for tumor progression, if only because they accelerate the rate at which evolving premalignant cells can accumulate favorable genotypes. As such, genome instability is clearly an enabling characteristic that is causally associated with the acquisition of hallmark capabilities.",
-    },
-    Hallmark {
-        title: "Deregulating cellular energetics",
-        description: "The chronic and often uncontrolled cell proliferation that represents the essence of neoplastic disease involves not only deregulated control of cell proliferation but also corresponding adjustments of energy metabolism in order to fuel cell growth and division. Under aerobic conditions, normal cells process glucose, first to pyruvate via glycolysis in the cytosol and thereafter to carbon dioxide in the mitochondria; under anaerobic conditions, glycolysis is favored and relatively little pyruvate is dispatched to the oxygen-consuming mitochondria. Otto Warburg first observed an anomalous characteristic of cancer cell energy metabolism (Warburg, 1930, Warburg, 1956a, Warburg, 1956b): even in the presence of oxygen, cancer cells can reprogram their glucose metabolism, and thus their energy production, by limiting their energy metabolism largely to glycolysis, leading to a state that has been termed “aerobic glycolysis.”
-The existence of this metabolic switch in cancer cells has been substantiated in the ensuing decades. Such reprogramming of energy metabolism is seemingly counterintuitive, in that cancer cells must compensate for the ∼18-fold lower efficiency of ATP production afforded by glycolysis relative to mitochondrial oxidative phosphorylation. They do so in part by upregulating glucose transporters, notably GLUT1, which substantially increases glucose import into the cytoplasm (Jones and Thompson, 2009, DeBerardinis et al., 2008, Hsu and Sabatini, 2008). Indeed, markedly increased uptake and utilization of glucose have been documented in many human tumor types, most readily by noninvasively visualizing glucose uptake using positron emission tomography (PET) with a radiolabeled analog of glucose (18F-fluorodeoxyglucose, FDG) as a reporter.
-Glycolytic fueling has been shown to be associated with activated oncogenes (e.g., RAS, MYC) and mutant tumor suppressors (e.g., TP53) (DeBerardinis et al., 2008, Jones and Thompson, 2009), whose alterations in tumor cells have been selected primarily for their benefits in conferring the hallmark capabilities of cell proliferation, avoidance of cytostatic controls, and attenuation of apoptosis. This reliance on glycolysis can be further accentuated under the hypoxic conditions that operate within many tumors: the hypoxia response system acts pleiotropically to upregulate glucose transporters and multiple enzymes of the glycolytic pathway (Semenza, 2010a, Jones and Thompson, 2009, DeBerardinis et al., 2008). Thus, both the Ras oncoprotein and hypoxia can independently increase the levels of the HIF1α and HIF2α transcription factors, which in turn upregulate glycolysis (Semenza, 2010a, Semenza, 2010b, Kroemer and Pouyssegur, 2008).
-A functional rationale for the glycolytic switch in cancer cells has been elusive, given the relatively poor efficiency of generating ATP by glycolysis relative to mitochondrial oxidative phosphorylation. According to one long-forgotten (Potter, 1958) and recently revived and refined hypothesis (Vander Heiden et al., 2009), increased glycolysis allows the diversion of glycolytic intermediates into various biosynthetic pathways, including those generating nucleosides and amino acids; this facilitates, in turn, the biosynthesis of the macromolecules and organelles required for assembling new cells. Moreover, Warburg-like metabolism seems to be present in many rapidly dividing embryonic tissues, once again suggesting a role in supporting the large-scale biosynthetic programs that are required for active cell proliferation.
-Interestingly, some tumors have been found to contain two subpopulations of cancer cells that differ in their energy-generating pathways. One subpopulation consists of glucose-dependent (“Warburg-effect”) cells that secrete lactate, whereas cells of the second subpopulation preferentially import and utilize the lactate produced by their neighbors as their main energy source, employing part of the citric acid cycle to do so (Kennedy and Dewhirst, 2010, Feron, 2009, Semenza, 2008). These two populations evidently function symbiotically: the hypoxic cancer cells depend on glucose for fuel and secrete lactate as waste, which is imported and preferentially used as fuel by their better-oxygenated brethren. Although this provocative mode of intratumoral symbiosis has yet to be generalized, the cooperation between lactate-secreting and lactate-utilizing cells to fuel tumor growth is in fact not an invention of tumors but rather again reflects cooption of a normal physiological mechanism, in this case one operating in muscle (Kennedy and Dewhirst, 2010, Feron, 2009, Semenza, 2008). Additionally, it is becoming apparent that oxygenation, ranging from normoxia to hypoxia, is not necessarily static in tumors but instead fluctuates temporally and regionally (Hardee et al., 2009), likely as a result of the instability and chaotic organization of the tumor-associated neovasculature.
-Altered energy metabolism is proving to be as widespread in cancer cells as many of the other cancer-associated traits that have been accepted as hallmarks of cancer. This realization raises the question of whether deregulating cellular energy metabolism is therefore a core hallmark capability of cancer cells that is as fundamental as the six well-established core hallmarks. In fact, the redirection of energy metabolism is largely orchestrated by proteins that are involved in one way or another in programming the core hallmarks of cancer. When viewed in this way, aerobic glycolysis is simply another phenotype that is programmed by proliferation-inducing oncogenes.
-Interestingly, activating (gain-of-function) mutations in the isocitrate dehydrogenase 1/2 (IDH) enzymes have been reported in glioma and other human tumors (Yen et al., 2010). Although these mutations may prove to have been clonally selected for their ability to alter energy metabolism, there is confounding data associating their activity with elevated oxidation and stability of the HIF-1 transcription factors (Reitman and Yan, 2010), which could in turn affect genome stability and angiogenesis/invasion, respectively, thus blurring the lines of phenotypic demarcation. Currently, therefore, the designation of reprogrammed energy metabolism as an emerging hallmark seems most appropriate, to highlight both its evident importance as well as the unresolved issues surrounding its functional independence from the core hallmarks.",
-    },
-    Hallmark {
-        title: "Avoiding immune destruction",
-        description: "A second, still-unresolved issue surrounding tumor formation involves the role that the immune system plays in resisting or eradicating formation and progression of incipient neoplasias, late-stage tumors, and micrometastases. The long-standing theory of immune surveillance proposes that cells and tissues are constantly monitored by an ever-alert immune system, and that such immune surveillance is responsible for recognizing and eliminating the vast majority of incipient cancer cells and thus nascent tumors. According to this logic, solid tumors that do appear have somehow managed to avoid detection by the various arms of the immune system or have been able to limit the extent of immunological killing, thereby evading eradication.
-The role of defective immunological monitoring of tumors would seem to be validated by the striking increases of certain cancers in immunocompromised individuals (Vajdic and van Leeuwen, 2009). However, the great majority of these are virus-induced cancers, suggesting that much of the control of this class of cancers normally depends on reducing viral burden in infected individuals, in part through eliminating virus-infected cells. These observations, therefore, seem to shed little light on the possible role of the immune system in limiting formation of the >80% of tumors of nonviral etiology. In recent years, however, an increasing body of evidence, both from genetically engineered mice and from clinical epidemiology, suggests that the immune system operates as a significant barrier to tumor formation and progression, at least in some forms of non-virus-induced cancer.
-When mice genetically engineered to be deficient for various components of the immune system were assessed for the development of carcinogen-induced tumors, it was observed that tumors arose more frequently and/or grew more rapidly in the immunodeficient mice relative to immunocompetent controls. In particular, deficiencies in the development or function of CD8+ cytotoxic T lymphocytes (CTLs), CD4+ Th1 helper T cells, or natural killer (NK) cells each led to demonstrable increases in tumor incidence; moreover, mice with combined immunodeficiencies in both T cells and NK cells were even more susceptible to cancer development. The results indicated that, at least in certain experimental models, both the innate and adaptive cellular arms of the immune system are able to contribute significantly to immune surveillance and thus tumor eradication (Teng et al., 2008, Kim et al., 2007).
-In addition, transplantation experiments have shown that cancer cells that originally arose in immunodeficient mice are often inefficient at initiating secondary tumors in syngeneic immunocompetent hosts, whereas cancer cells from tumors arising in immunocompetent mice are equally efficient at initiating transplanted tumors in both types of hosts (Teng et al., 2008, Kim et al., 2007). Such behavior has been interpreted as follows: Highly immunogenic cancer cell clones are routinely eliminated in immunocompetent hosts—a process that has been referred to as “immunoediting”—leaving behind only weakly immunogenic variants to grow and generate solid tumors; such weakly immunogenic cells can thereafter colonize both immunodeficient and immunocompetent hosts. Conversely, when arising in immunodeficient hosts, the immunogenic cancer cells are not selectively depleted and can, instead, prosper along with their weakly immunogenic counterparts. When cells from such nonedited tumors are serially transplanted into syngeneic recipients, the immunogenic cancer cells are rejected when they confront, for the first time, the competent immune systems of their secondary hosts (Smyth et al., 2006). (Unanswered in these particular experiments is the question of whether the chemical carcinogens used to induce such tumors are prone to generate cancer cells that are especially immunogenic.)
-Clinical epidemiology also increasingly supports the existence of antitumoral immune responses in some forms of human cancer (Bindea et al., 2010, Ferrone and Dranoff, 2010, Nelson, 2008). For example, patients with colon and ovarian tumors that are heavily infiltrated with CTLs and NK cells have a better prognosis than those that lack such abundant killer lymphocytes (Pagès et al., 2010, Nelson, 2008); the case for other cancers is suggestive but less compelling and is the subject of ongoing investigation. Additionally, some immunosuppressed organ transplant recipients have been observed to develop donor-derived cancers, suggesting that in the ostensibly tumor-free donors, the cancer cells were held in check, in a dormant state, by a fully functional immune system (Strauss and Thomas, 2010).
-Still, the epidemiology of chronically immunosuppressed patients does not indicate significantly increased incidences of the major forms of nonviral human cancer, as noted above. This might be taken as an argument against the importance of immune surveillance as an effective barrier to tumorigenesis and tumor progression. We note, however, that HIV and pharmacologically immunosuppressed patients are predominantly immunodeficient in the T and B cell compartments and thus do not present with the multicomponent immunological deficiencies that have been produced in the genetically engineered mutant mice lacking both NK cells and CTLs; this leaves open the possibility that such patients still have residual capability for an immunological defense against cancer that is mounted by NK and other innate immune cells.
-In truth, the above discussions of cancer immunology simplify tumor-host immunological interactions, as highly immunogenic cancer cells may well evade immune destruction by disabling components of the immune system that have been dispatched to eliminate them. For example, cancer cells may paralyze infiltrating CTLs and NK cells, by secreting TGF-β or other immunosuppressive factors (Yang et al., 2010, Shields et al., 2010). More subtle mechanisms operate through the recruitment of inflammatory cells that are actively immunosuppressive, including regulatory T cells (Tregs) and myeloid-derived suppressor cells (MDSCs). Both can suppress the actions of cytotoxic lymphocytes (Mougiakakos et al., 2010, Ostrand-Rosenberg and Sinha, 2009).
-In light of these considerations and the still-rudimentary demonstrations of antitumor immunity as a significant barrier to tumor formation and progression in humans, we present immunoevasion as another emerging hallmark, whose generality as a core hallmark capability remains to be firmly established.",
-    },
-    Hallmark {
-        title: "Tumor-promoting inflammation",
-        description: "Pathologists have long recognized that some tumors are densely infiltrated by cells of both the innate and adaptive arms of the immune system and thereby mirror inflammatory conditions arising in non-neoplastic tissues (Dvorak, 1986). With the advent of better markers for accurately identifying the distinct cell types of the immune system, it is now clear that virtually every neoplastic lesion contains immune cells present at densities ranging from subtle infiltrations detectable only with cell type-specific antibodies to gross inflammations that are apparent even by standard histochemical staining techniques (Pagès et al., 2010). Historically, such immune responses were largely thought to reflect an attempt by the immune system to eradicate tumors, and indeed, there is increasing evidence for antitumoral responses to many tumor types with an attendant pressure on the tumor to evade immune destruction, as discussed below.
-By 2000, there were already clues that the tumor-associated inflammatory response had the unanticipated, paradoxical effect of enhancing tumorigenesis and progression, in effect helping incipient neoplasias to acquire hallmark capabilities. In the ensuing decade, research on the intersections between inflammation and cancer pathogenesis has blossomed, producing abundant and compelling demonstrations of the functionally important tumor-promoting effects that immune cells—largely of the innate immune system—have on neoplastic progression (DeNardo et al., 2010, Grivennikov et al., 2010, Qian and Pollard, 2010, Colotta et al., 2009). Inflammation can contribute to multiple hallmark capabilities by supplying bioactive molecules to the tumor microenvironment, including growth factors that sustain proliferative signaling, survival factors that limit cell death, proangiogenic factors, extracellular matrix-modifying enzymes that facilitate angiogenesis, invasion, and metastasis, and inductive signals that lead to activation of EMT and other hallmark-facilitating programs (DeNardo et al., 2010, Grivennikov et al., 2010, Qian and Pollard, 2010, Karnoub and Weinberg, 2006–2007).
-Importantly, inflammation is in some cases evident at the earliest stages of neoplastic progression and is demonstrably capable of fostering the development of incipient neoplasias into full-blown cancers (Qian and Pollard, 2010, de Visser et al., 2006). Additionally, inflammatory cells can release chemicals, notably reactive oxygen species, that are actively mutagenic for nearby cancer cells, accelerating their genetic evolution toward states of heightened malignancy (Grivennikov et al., 2010). As such, inflammation can be considered an enabling characteristic for its contributions to the acquisition of core hallmark capabilities. The cells responsible for this enabling characteristic are described in the section below on the tumor microenvironment.",
-    },
-];
+/// A hallmark given by its index or its title.
+fn parse_hallmark_index(flag: &str, value: Option<String>) -> usize {
+    let value = expect_value(flag, value);
+    if let Some(index) = hallmark_index(&value) {
+        return index;
+    }
+    let index: usize = parse_value(flag, Some(value));
+    if index >= DEFAULT_HALLMARKS.len() {
+        exit_with_error(&format!(
+            "Invalid hallmark index for {}: {}, there are {} hallmarks",
+            flag,
+            index,
+            DEFAULT_HALLMARKS.len()
+        ));
+    }
+    index
+}
+
+fn load_file<T>(flag: &str, value: Option<String>, loader: fn(&str) -> std::io::Result<T>) -> T {
+    let path = expect_value(flag, value);
+    loader(&path).unwrap_or_else(|e| exit_with_error(&format!("Could not load {}: {}", path, e)))
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,84 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+/// Version of the schema of the rating and article databases, written into both. Bump it
+/// whenever the schema changes. Files written before versioning count as version 0.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Rejects databases written by a newer version of the crate, whose schema is unknown.
+pub fn check_format_version(path: &str, format_version: u32) -> io::Result<()> {
+    if format_version > FORMAT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{}: format version {} is newer than the supported version {}",
+                path, format_version, FORMAT_VERSION
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `path` names a JSON Lines file, with one record per line instead of a single JSON
+/// document, also when gzip-compressed.
+pub fn is_jsonl(path: &str) -> bool {
+    path.strip_suffix(".gz").unwrap_or(path).ends_with(".jsonl")
+}
+
+/// Reads a file written through `OutputFile`, decompressing it when its path ends in `.gz`.
+pub fn read_output_file(path: &str) -> io::Result<String> {
+    if !path.ends_with(".gz") {
+        return std::fs::read_to_string(path);
+    }
+    let mut contents = String::new();
+    GzDecoder::new(BufReader::new(File::open(path)?)).read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// An output database file, gzip-compressed when its path ends in `.gz`.
+pub enum OutputFile {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutputFile {
+    pub fn create(path: &str) -> io::Result<OutputFile> {
+        let file = BufWriter::new(File::create(path)?);
+        if path.ends_with(".gz") {
+            Ok(OutputFile::Gzip(GzEncoder::new(
+                file,
+                Compression::default(),
+            )))
+        } else {
+            Ok(OutputFile::Plain(file))
+        }
+    }
+
+    /// Writes the gzip trailer, if any, and flushes the file. Dropping an `OutputFile`
+    /// without calling this silently ignores write errors.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(mut file) => file.flush(),
+            OutputFile::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputFile::Plain(file) => file.write(buf),
+            OutputFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(file) => file.flush(),
+            OutputFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::analyzer::RatedPublication;
+use crate::analyzer_data::by_descending_score;
+
+/// Document format of a `PublicationReport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("unknown report format '{}'", s)),
+        }
+    }
+}
+
+/// Rated publications grouped by their dominant hallmark, for a document a domain expert
+/// can read. Sections follow the order of the hallmarks and leave out hallmarks that
+/// dominate no publication. Within a section the most confident publications come first.
+#[derive(Debug, Clone)]
+pub struct PublicationReport<'a> {
+    /// Titles of the hallmarks the ratings are indexed by.
+    pub hallmarks: &'a [String],
+    /// Hallmark indices with their publications and the confidence of each.
+    pub sections: Vec<(usize, Vec<(&'a RatedPublication, f32)>)>,
+}
+
+impl<'a> PublicationReport<'a> {
+    /// Groups `publications` rated with `hallmarks`, using their written confidence if they
+    /// have one and computing it otherwise. Fails for a publication with another number of
+    /// ratings than hallmarks.
+    pub fn new(
+        publications: &'a [RatedPublication],
+        hallmarks: &'a [String],
+    ) -> Result<Self, String> {
+        if let Some(publication) = publications.iter().find(|p| p.r.len() != hallmarks.len()) {
+            return Err(format!(
+                "publication '{}' has {} hallmark ratings instead of {}",
+                publication.i,
+                publication.r.len(),
+                hallmarks.len()
+            ));
+        }
+        let mut sections: Vec<Vec<(&RatedPublication, f32)>> = vec![vec![]; hallmarks.len()];
+        for publication in publications.iter() {
+            if let Some(hallmark) = publication.dominant_hallmark() {
+                let confidence = publication.c.unwrap_or_else(|| publication.confidence());
+                sections[hallmark].push((publication, confidence));
+            }
+        }
+        for section in sections.iter_mut() {
+            section.sort_by(|a, b| by_descending_score((a.1, &a.0.i), (b.1, &b.0.i)));
+        }
+        Ok(PublicationReport {
+            hallmarks,
+            sections: sections
+                .into_iter()
+                .enumerate()
+                .filter(|(_, section)| !section.is_empty())
+                .collect(),
+        })
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.render_markdown(),
+            ReportFormat::Html => self.render_html(),
+        }
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut ret = String::from("# Rated publications\n");
+        for (hallmark, publications) in self.sections.iter() {
+            ret.push_str(&format!(
+                "\n## {} ({})\n\n| Publication | Rating | Confidence |\n| --- | --- | --- |\n",
+                self.hallmarks[*hallmark],
+                publications.len()
+            ));
+            for (publication, confidence) in publications.iter() {
+                ret.push_str(&format!(
+                    "| {} | {:.3} | {:.3} |\n",
+                    publication.i.replace('|', "\\|"),
+                    publication.r[*hallmark],
+                    confidence
+                ));
+            }
+        }
+        ret
+    }
+
+    fn render_html(&self) -> String {
+        let mut ret = String::from("<h1>Rated publications</h1>\n");
+        for (hallmark, publications) in self.sections.iter() {
+            ret.push_str(&format!(
+                "<h2>{} ({})</h2>\n<table>\n<tr><th>Publication</th><th>Rating</th><th>Confidence</th></tr>\n",
+                escape_html(&self.hallmarks[*hallmark]),
+                publications.len()
+            ));
+            for (publication, confidence) in publications.iter() {
+                ret.push_str(&format!(
+                    "<tr><td>{}</td><td>{:.3}</td><td>{:.3}</td></tr>\n",
+                    escape_html(&publication.i),
+                    publication.r[*hallmark],
+                    confidence
+                ));
+            }
+            ret.push_str("</table>\n");
+        }
+        ret
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,98 @@
+use crate::analyzer_data::by_descending_score;
+use crate::ensemble::RatingModel;
+
+const MAX_LISTED_KEYWORDS: usize = 20;
+
+/// Differences between two rating databases, e.g. before and after changing the cutoffs.
+#[derive(Debug, Clone, Default)]
+pub struct RatingDiff {
+    /// Titles of the hallmarks of the new database, which index the changes.
+    pub hallmarks: Vec<String>,
+    /// Keywords only in the new database, sorted.
+    pub added: Vec<String>,
+    /// Keywords only in the old database, sorted.
+    pub removed: Vec<String>,
+    /// Keywords in both databases with the per-hallmark change from old to new, largest
+    /// total change first. Hallmarks the old database lacks count as rated 0 there.
+    pub changes: Vec<(String, Vec<f32>)>,
+}
+
+impl RatingDiff {
+    pub fn between(old: &RatingModel, new: &RatingModel) -> RatingDiff {
+        let mut diff = RatingDiff {
+            hallmarks: new.hallmarks().to_vec(),
+            ..Default::default()
+        };
+        let old_indices: Vec<Option<usize>> = new
+            .hallmarks()
+            .iter()
+            .map(|title| old.hallmark_index(title))
+            .collect();
+        for keyword in new.keywords() {
+            match (old.rating(keyword), new.rating(keyword)) {
+                (Some(before), Some(after)) => {
+                    let delta = after
+                        .iter()
+                        .zip(old_indices.iter())
+                        .map(|(a, index)| a - index.map_or(0.0, |i| before[i]))
+                        .collect();
+                    diff.changes.push((keyword.to_string(), delta));
+                }
+                _ => diff.added.push(keyword.to_string()),
+            }
+        }
+        diff.removed = old
+            .keywords()
+            .filter(|k| new.rating(k).is_none())
+            .map(|k| k.to_string())
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changes.sort_by(|a, b| {
+            by_descending_score((total_change(&a.1), &a.0), (total_change(&b.1), &b.0))
+        });
+        diff
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{} keywords added, {} removed, {} in both databases.",
+            self.added.len(),
+            self.removed.len(),
+            self.changes.len()
+        );
+        print_keywords("Added", &self.added);
+        print_keywords("Removed", &self.removed);
+        println!("Largest rating changes:");
+        for (keyword, delta) in self.changes.iter().take(MAX_LISTED_KEYWORDS) {
+            println!("  {} (total {:.3}):", keyword, total_change(delta));
+            for (hallmark, change) in self.hallmarks.iter().zip(delta.iter()) {
+                if change.abs() >= 0.001 {
+                    println!("    {}: {:+.3}", hallmark, change);
+                }
+            }
+        }
+    }
+}
+
+/// The sum of the absolute per-hallmark changes.
+fn total_change(delta: &[f32]) -> f32 {
+    delta.iter().map(|d| d.abs()).sum()
+}
+
+fn print_keywords(label: &str, keywords: &[String]) {
+    if keywords.is_empty() {
+        return;
+    }
+    let listed: Vec<&str> = keywords
+        .iter()
+        .take(MAX_LISTED_KEYWORDS)
+        .map(|k| k.as_str())
+        .collect();
+    let ellipsis = if keywords.len() > MAX_LISTED_KEYWORDS {
+        ", ..."
+    } else {
+        ""
+    };
+    println!("{}: {}{}", label, listed.join(", "), ellipsis);
+}
@@ -0,0 +1,132 @@
+use serde_json::Value;
+
+use crate::analyzer_data::RATING_SUM_TOLERANCE;
+use crate::output::{read_output_file, FORMAT_VERSION};
+use crate::DEFAULT_HALLMARKS;
+
+const MAX_LISTED_PROBLEMS: usize = 20;
+
+/// Problems found in a rating database before it is used for scoring, e.g. because the file
+/// is corrupted or was written for another set of hallmarks.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub n_keywords: usize,
+    /// One line per problem, in the order of the database.
+    pub problems: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Checks a rating database as serialized, without the rounding and defaults of loading
+    /// it: the format version, the hallmarks against `DEFAULT_HALLMARKS`, and every keyword
+    /// rating for its length, for values that are no finite numbers, and for rated
+    /// keywords that do not sum to 1. Fails only if the file is no JSON at all.
+    pub fn of_file(path: &str) -> std::io::Result<ValidationReport> {
+        let database: Value = serde_json::from_str(&read_output_file(path)?)?;
+        Ok(ValidationReport::of_database(&database))
+    }
+
+    pub fn of_database(database: &Value) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let format_version = database["format_version"].as_u64().unwrap_or(0);
+        if format_version > FORMAT_VERSION as u64 {
+            report.problems.push(format!(
+                "format version {} is newer than the supported version {}",
+                format_version, FORMAT_VERSION
+            ));
+        }
+        report.check_hallmarks(&database["hallmarks"]);
+        let Some(entries) = database["rating_output"].as_array() else {
+            report
+                .problems
+                .push("the database has no rating_output array".to_string());
+            return report;
+        };
+        report.n_keywords = entries.len();
+        for entry in entries.iter() {
+            report.check_keyword(entry);
+        }
+        report
+    }
+
+    fn check_hallmarks(&mut self, hallmarks: &Value) {
+        let Some(hallmarks) = hallmarks.as_array() else {
+            self.problems
+                .push("the database does not list its hallmarks".to_string());
+            return;
+        };
+        if hallmarks.len() != DEFAULT_HALLMARKS.len() {
+            self.problems.push(format!(
+                "the database lists {} hallmarks instead of {}",
+                hallmarks.len(),
+                DEFAULT_HALLMARKS.len()
+            ));
+        }
+        for (index, (hallmark, expected)) in hallmarks.iter().zip(DEFAULT_HALLMARKS).enumerate() {
+            let title = hallmark["title"].as_str().unwrap_or("");
+            if title != expected.title {
+                self.problems.push(format!(
+                    "hallmark {} is '{}' instead of '{}'",
+                    index, title, expected.title
+                ));
+            }
+        }
+    }
+
+    fn check_keyword(&mut self, entry: &Value) {
+        let keyword = entry["keyword"].as_str().unwrap_or("<missing keyword>");
+        let Some(rating) = entry["rating"].as_array() else {
+            self.problems
+                .push(format!("keyword '{}' has no rating", keyword));
+            return;
+        };
+        if rating.len() != DEFAULT_HALLMARKS.len() {
+            self.problems.push(format!(
+                "keyword '{}' has {} hallmark ratings instead of {}",
+                keyword,
+                rating.len(),
+                DEFAULT_HALLMARKS.len()
+            ));
+        }
+        let values: Vec<Option<f64>> = rating
+            .iter()
+            .map(|value| match value {
+                Value::String(s) => s.parse().ok(),
+                other => other.as_f64(),
+            })
+            .collect();
+        if values.iter().any(|v| !v.is_some_and(f64::is_finite)) {
+            self.problems
+                .push(format!("keyword '{}' has a NaN or missing rating", keyword));
+            return;
+        }
+        let sum: f64 = values.iter().flatten().sum();
+        if sum != 0.0 && (sum - 1.0).abs() > RATING_SUM_TOLERANCE as f64 {
+            self.problems.push(format!(
+                "ratings of keyword '{}' sum to {} instead of 1",
+                keyword, sum
+            ));
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn print(&self) {
+        if self.is_valid() {
+            println!("All {} keyword ratings are valid.", self.n_keywords);
+            return;
+        }
+        println!(
+            "{} problems in the ratings of {} keywords:",
+            self.problems.len(),
+            self.n_keywords
+        );
+        for problem in self.problems.iter().take(MAX_LISTED_PROBLEMS) {
+            println!("  {}", problem);
+        }
+        if self.problems.len() > MAX_LISTED_PROBLEMS {
+            println!("  ...");
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::config::AnalyzerConfig;
+use crate::{default_hallmark_titles, DEFAULT_HALLMARKS};
+
+const MAX_LISTED_TERMS: usize = 10;
+
+/// Prints a status line of a pipeline phase, unless the run is quiet.
+pub(crate) fn report(config: &AnalyzerConfig, message: &str) {
+    if !config.quiet {
+        println!("{}", message);
+    }
+}
+
+/// Collected statistics of a full analyzer run, printed once all phases are done.
+#[derive(Debug, Default, Clone)]
+pub struct RunSummary {
+    /// Vocabulary size after each filtering stage, in pipeline order.
+    pub vocabulary_sizes: Vec<(String, usize)>,
+    /// Seed terms of each hallmark that are missing from the vocabulary.
+    pub unmatched_seed_terms: Vec<Vec<String>>,
+    /// Articles skipped as malformed, see `AnalyzerConfig::skip_malformed_articles`.
+    pub n_malformed_articles: usize,
+    /// Articles skipped for `AnalyzerConfig::min_abstract_length`.
+    pub n_short_articles: usize,
+    /// Articles left out of the relations matrix for having fewer than
+    /// `AnalyzerConfig::min_relevant_keywords` vocabulary keywords.
+    pub n_sparse_articles: usize,
+    /// Structure of the keyword co-occurrence graph, `None` if no matrix was built.
+    pub connectivity: Option<RelationsConnectivity>,
+    /// Thematic composition of the rated publications.
+    pub corpus_distribution: CorpusDistribution,
+}
+
+/// Structure of the co-occurrence graph of the relations matrix, whose nodes are the
+/// keywords and whose edges are the nonzero entries off the diagonal.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RelationsConnectivity {
+    pub n_components: usize,
+    /// Keywords without any co-occurring partner. Rating propagation cannot reach them, so
+    /// they stay unrated unless they are seeded themselves.
+    pub n_isolated: usize,
+    /// Pairs of a degree and the number of keywords with exactly that many partners,
+    /// ascending by degree.
+    pub degree_histogram: Vec<(usize, usize)>,
+}
+
+impl RelationsConnectivity {
+    /// The degree of the median keyword, 0 for an empty graph.
+    pub fn median_degree(&self) -> usize {
+        let n_keywords: usize = self.degree_histogram.iter().map(|(_, count)| count).sum();
+        let mut seen = 0;
+        for (degree, count) in self.degree_histogram.iter() {
+            seen += count;
+            if 2 * seen > n_keywords {
+                return *degree;
+            }
+        }
+        0
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.degree_histogram
+            .last()
+            .map_or(0, |(degree, _)| *degree)
+    }
+}
+
+/// Aggregate hallmark ratings over all rated publications.
+#[derive(Debug, Clone)]
+pub struct CorpusDistribution {
+    pub n_publications: usize,
+    /// Titles of the hallmarks the ratings are indexed by, usually those of
+    /// `DEFAULT_HALLMARKS`.
+    pub hallmarks: Vec<String>,
+    /// Sum of the ratings per hallmark, indexed like `hallmarks`.
+    pub rating_sums: Vec<f32>,
+    /// Number of publications whose highest rating is the respective hallmark.
+    pub dominant_counts: Vec<usize>,
+    /// Articles that were not rated, by reason.
+    pub dropped: DroppedPublications,
+}
+
+/// Counts of articles left out of the article database.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DroppedPublications {
+    /// Articles without a PMC id, or without an id under `Analyzer::set_article_id`.
+    pub without_pmc: usize,
+    /// Articles whose abstract yields no tokens at all, e.g. because every word is too short.
+    pub empty_abstract: usize,
+    /// Articles whose rating does not sum to 1, mostly because none of their tokens is a
+    /// keyword.
+    pub invalid_rating: usize,
+    /// Articles with fewer than `AnalyzerConfig::min_relevant_keywords` vocabulary keywords.
+    pub too_few_keywords: usize,
+    /// Articles whose rating has NaN or infinite components although some of their keywords
+    /// are rated, see `RatedPublication::has_non_finite_rating`.
+    pub non_finite_rating: usize,
+}
+
+impl Default for CorpusDistribution {
+    fn default() -> Self {
+        CorpusDistribution::new(default_hallmark_titles())
+    }
+}
+
+impl CorpusDistribution {
+    pub fn new(hallmarks: Vec<String>) -> Self {
+        CorpusDistribution {
+            n_publications: 0,
+            rating_sums: vec![0.0; hallmarks.len()],
+            dominant_counts: vec![0; hallmarks.len()],
+            hallmarks,
+            dropped: DroppedPublications::default(),
+        }
+    }
+
+    pub fn add(&mut self, rating: &[f32]) {
+        self.n_publications += 1;
+        for (sum, value) in self.rating_sums.iter_mut().zip(rating.iter()) {
+            *sum += value;
+        }
+        let dominant = rating
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(hallmark, _)| hallmark);
+        if let Some(count) = dominant.and_then(|hallmark| self.dominant_counts.get_mut(hallmark)) {
+            *count += 1;
+        }
+    }
+
+    /// The mean rating per hallmark, all zero if no publication was rated.
+    pub fn mean_ratings(&self) -> Vec<f32> {
+        let n = self.n_publications.max(1) as f32;
+        self.rating_sums.iter().map(|sum| sum / n).collect()
+    }
+}
+
+impl RunSummary {
+    pub fn print(&self) {
+        println!("Run summary:");
+        for (stage, size) in self.vocabulary_sizes.iter() {
+            println!("  Vocabulary size {}: {}", stage, size);
+        }
+        for (hallmark, unmatched) in self.unmatched_seed_terms.iter().enumerate() {
+            if unmatched.is_empty() {
+                continue;
+            }
+            let listed: Vec<&str> = unmatched
+                .iter()
+                .take(MAX_LISTED_TERMS)
+                .map(|t| t.as_str())
+                .collect();
+            let ellipsis = if unmatched.len() > MAX_LISTED_TERMS {
+                ", ..."
+            } else {
+                ""
+            };
+            println!(
+                "  {} seed terms of '{}' are not in the vocabulary: {}{}",
+                unmatched.len(),
+                DEFAULT_HALLMARKS[hallmark].title,
+                listed.join(", "),
+                ellipsis
+            );
+        }
+        if self.n_malformed_articles > 0 {
+            println!(
+                "  Skipped malformed articles: {}",
+                self.n_malformed_articles
+            );
+        }
+        if self.n_short_articles > 0 {
+            println!(
+                "  Skipped articles with too short abstracts: {}",
+                self.n_short_articles
+            );
+        }
+        if self.n_sparse_articles > 0 {
+            println!(
+                "  Articles with too few keywords for the relations matrix: {}",
+                self.n_sparse_articles
+            );
+        }
+        if let Some(connectivity) = &self.connectivity {
+            println!(
+                "  Relations graph: {} connected components, {} isolated keywords, median degree {}, maximum degree {}",
+                connectivity.n_components,
+                connectivity.n_isolated,
+                connectivity.median_degree(),
+                connectivity.max_degree()
+            );
+        }
+        let distribution = &self.corpus_distribution;
+        if distribution.n_publications > 0 {
+            println!(
+                "  Hallmark distribution of {} rated publications (mean rating, dominant in):",
+                distribution.n_publications
+            );
+            for ((title, mean), count) in distribution
+                .hallmarks
+                .iter()
+                .zip(distribution.mean_ratings())
+                .zip(distribution.dominant_counts.iter())
+            {
+                println!("    {}: {:.3}, {}", title, mean, count);
+            }
+        }
+        let dropped = &distribution.dropped;
+        if *dropped != DroppedPublications::default() {
+            println!(
+                "  Dropped articles: {} without PMC id, {} with empty abstract after tokenization, {} with too few keywords, {} with invalid rating, {} with NaN or infinite rating",
+                dropped.without_pmc, dropped.empty_abstract, dropped.too_few_keywords, dropped.invalid_rating, dropped.non_finite_rating
+            );
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+
+use crate::article::Article;
+use crate::config::{format_input_filename, AnalyzerConfig, TokenizerConfig};
+use crate::tokenizer::Tokenizer;
+use crate::DEFAULT_HALLMARKS;
+
+const FILLER_WORDS: [&str; 8] = [
+    "the", "and", "with", "cells", "were", "found", "that", "this",
+];
+
+/// Parameters of a reproducible synthetic corpus for tests and demos. Every abstract is
+/// about one hallmark: it contains some of the words of the hallmark's title and mostly
+/// vocabulary words of the hallmark's topic, so keywords co-occur in topic clusters. The
+/// same parameters always produce the same corpus.
+#[derive(Debug, Clone)]
+pub struct SyntheticCorpus {
+    pub seed: u64,
+    pub n_files: usize,
+    pub articles_per_file: usize,
+    /// Number of made-up vocabulary words, spread evenly over the hallmark topics.
+    pub vocabulary_size: usize,
+    /// Vocabulary words per abstract, before hallmark terms and filler words are added.
+    pub words_per_abstract: usize,
+    /// Probability that a vocabulary word is drawn from the abstract's own topic rather than
+    /// from the whole vocabulary.
+    pub topic_affinity: f64,
+    /// Words of the hallmark title injected into each abstract.
+    pub hallmark_terms_per_abstract: usize,
+}
+
+/// The defaults are sized so that most vocabulary words survive the default cutoffs, which
+/// scale with the number of files.
+impl Default for SyntheticCorpus {
+    fn default() -> Self {
+        SyntheticCorpus {
+            seed: 1,
+            n_files: 100,
+            articles_per_file: 2,
+            vocabulary_size: 100,
+            words_per_abstract: 6,
+            topic_affinity: 0.8,
+            hallmark_terms_per_abstract: 1,
+        }
+    }
+}
+
+impl SyntheticCorpus {
+    /// The articles of every file, in file order. Article `i` of the corpus is about
+    /// hallmark `i % DEFAULT_HALLMARKS.len()` and has the PMC id `PMC{i}`.
+    pub fn articles(&self) -> Vec<Vec<Article>> {
+        let mut rng = SplitMix64(self.seed);
+        let tokenizer = Tokenizer::new(&TokenizerConfig::default()).unwrap();
+        let hallmark_terms: Vec<Vec<String>> = DEFAULT_HALLMARKS
+            .iter()
+            .map(|h| tokenizer.unique_tokens(h.title))
+            .collect();
+        let vocabulary: Vec<String> = (0..self.vocabulary_size).map(synthetic_word).collect();
+        let n_hallmarks = DEFAULT_HALLMARKS.len();
+
+        let mut files = vec![];
+        for file in 0..self.n_files {
+            let mut articles = vec![];
+            for position in 0..self.articles_per_file {
+                let index = file * self.articles_per_file + position;
+                let hallmark = index % n_hallmarks;
+                let mut words: Vec<&str> = vec![];
+                let terms = &hallmark_terms[hallmark];
+                for _ in 0..self.hallmark_terms_per_abstract {
+                    if !terms.is_empty() {
+                        words.push(&terms[rng.below(terms.len())]);
+                    }
+                }
+                let topic: Vec<&String> = vocabulary
+                    .iter()
+                    .skip(hallmark)
+                    .step_by(n_hallmarks)
+                    .collect();
+                for _ in 0..self.words_per_abstract {
+                    if !topic.is_empty() && rng.unit() < self.topic_affinity {
+                        words.push(topic[rng.below(topic.len())]);
+                    } else if !vocabulary.is_empty() {
+                        words.push(&vocabulary[rng.below(vocabulary.len())]);
+                    }
+                }
+                for _ in 0..3 {
+                    words.push(FILLER_WORDS[rng.below(FILLER_WORDS.len())]);
+                }
+                articles.push(Article {
+                    title: format!("Synthetic article {}", index),
+                    pmid: index.to_string(),
+                    doi: String::new(),
+                    pmc: format!("PMC{}", index),
+                    pii: String::new(),
+                    paper_abstract: format!("{}.", words.join(" ")),
+                });
+            }
+            files.push(articles);
+        }
+        files
+    }
+
+    /// Writes the corpus to `dir` as one JSON array per file, named after the default
+    /// `input_file_template` so that input file detection finds them. Returns the paths of
+    /// the written files.
+    pub fn write(&self, dir: &Path) -> std::io::Result<Vec<String>> {
+        let config = AnalyzerConfig::default();
+        let mut filenames = vec![];
+        for (i, articles) in self.articles().iter().enumerate() {
+            let path = dir.join(format_input_filename(
+                &config.input_file_template,
+                config.first_input_index + i,
+            ));
+            fs::write(&path, serde_json::to_string(articles)?)?;
+            filenames.push(path.to_string_lossy().to_string());
+        }
+        Ok(filenames)
+    }
+}
+
+/// Made-up word number `index`, long enough to survive tokenization.
+fn synthetic_word(index: usize) -> String {
+    let mut word = String::from("synth");
+    let mut rest = index;
+    loop {
+        word.push((b'a' + (rest % 26) as u8) as char);
+        rest /= 26;
+        if rest == 0 {
+            return word;
+        }
+    }
+}
+
+/// A small deterministic random number generator, so the corpus does not depend on the
+/// platform or on an external crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,210 @@
+use regex::Regex;
+
+use crate::config::{HyphenMode, TokenizerConfig, UNIT_WORDS};
+
+/// Splits abstracts and hallmark descriptions into keyword tokens. All regular expressions
+/// are compiled once on construction, so one instance should be reused for a whole phase.
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    config: TokenizerConfig,
+    punctuation: Regex,
+    protected: Vec<Regex>,
+    /// Any symbol of `UNIT_WORDS`, longest first.
+    units: Regex,
+}
+
+impl Tokenizer {
+    pub fn new(config: &TokenizerConfig) -> Result<Tokenizer, regex::Error> {
+        let mut protected = vec![];
+        for pattern in config.protected_patterns.iter() {
+            protected.push(Regex::new(pattern)?);
+        }
+        Ok(Tokenizer {
+            config: config.clone(),
+            punctuation: Regex::new(r#"[.?,;()!\/'"%=]"#).unwrap(),
+            protected,
+            units: Regex::new(
+                &UNIT_WORDS
+                    .iter()
+                    .map(|(symbol, _)| regex::escape(symbol))
+                    .collect::<Vec<_>>()
+                    .join("|"),
+            )
+            .unwrap(),
+        })
+    }
+
+    pub fn config(&self) -> &TokenizerConfig {
+        &self.config
+    }
+
+    /// Returns the sorted tokens of `text`, including repetitions. Protected matches are
+    /// kept however short they are, so e.g. "50%" survives the length filter.
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut text = text.to_lowercase();
+        if self.config.unit_words {
+            text = self.unit_words(&text);
+        }
+        text = self.transliterate(text);
+        let mut ret: Vec<String> = vec![];
+        let mut protected_tokens: Vec<String> = vec![];
+        for protected in self.protected.iter() {
+            if !self.config.drop_protected {
+                protected_tokens.extend(
+                    protected
+                        .find_iter(&text)
+                        .map(|m| m.as_str().split_whitespace().collect::<String>()),
+                );
+            }
+            text = protected.replace_all(&text, " ").to_string();
+        }
+        let cleared = self.punctuation.replace_all(&text, " ").to_string();
+        for w in cleared.split_whitespace() {
+            let word = clean_keyword(w.to_string());
+            if self.config.hyphen_mode != HyphenMode::Keep && word.contains('-') {
+                ret.extend(word.split('-').map(|part| clean_keyword(part.to_string())));
+                if self.config.hyphen_mode == HyphenMode::Split {
+                    continue;
+                }
+            }
+            ret.push(word);
+        }
+        ret.retain(|w| w.len() > 4);
+        ret.extend(protected_tokens);
+        for word in ret.iter_mut() {
+            let canonical = self.canonical(word);
+            if canonical != word {
+                *word = canonical.to_string();
+            }
+        }
+        ret.sort();
+        ret
+    }
+
+    /// The sorted distinct tokens of `text` with their number of occurrences.
+    pub fn token_counts(&self, text: &str) -> Vec<(String, usize)> {
+        let mut ret: Vec<(String, usize)> = vec![];
+        for token in self.tokenize(text) {
+            match ret.last_mut() {
+                Some((last, count)) if *last == token => *count += 1,
+                _ => ret.push((token, 1)),
+            }
+        }
+        ret
+    }
+
+    /// Replaces the unit symbols of `text` by their `UNIT_WORDS`, where they stand on their
+    /// own or right after a number. A symbol starting a longer word, like "µm" in "µmol", is
+    /// left alone.
+    fn unit_words(&self, text: &str) -> String {
+        let mut ret = String::with_capacity(text.len());
+        let mut last = 0;
+        for m in self.units.find_iter(text) {
+            let before = text[..m.start()].chars().next_back();
+            let after = text[m.end()..].chars().next();
+            if before.is_some_and(char::is_alphabetic) || after.is_some_and(char::is_alphanumeric) {
+                continue;
+            }
+            let (_, word) = UNIT_WORDS
+                .iter()
+                .find(|(symbol, _)| *symbol == m.as_str())
+                .unwrap();
+            ret.push_str(&text[last..m.start()]);
+            ret.push(' ');
+            ret.push_str(word);
+            ret.push(' ');
+            last = m.end();
+        }
+        ret.push_str(&text[last..]);
+        ret
+    }
+
+    fn transliterate(&self, text: String) -> String {
+        let transliterations = &self.config.transliterations;
+        if transliterations.is_empty() || text.is_ascii() {
+            return text;
+        }
+        let mut ret = String::with_capacity(text.len());
+        for c in text.chars() {
+            match transliterations.get(&c) {
+                Some(latin) => ret.push_str(latin),
+                None => ret.push(c),
+            }
+        }
+        ret
+    }
+
+    /// The canonical form of a single term, e.g. a curated seed keyword: its lemma, replaced
+    /// by its synonym if it has one.
+    pub fn canonical<'a>(&'a self, term: &'a str) -> &'a str {
+        let lemma = self
+            .config
+            .lemmas
+            .get(term)
+            .map(|l| l.as_str())
+            .unwrap_or(term);
+        self.config
+            .synonyms
+            .get(lemma)
+            .map(|c| c.as_str())
+            .unwrap_or(lemma)
+    }
+
+    /// Returns the sorted tokens of `text` with every token occurring once.
+    pub fn unique_tokens(&self, text: &str) -> Vec<String> {
+        let mut ret = self.tokenize(text);
+        ret.dedup();
+        ret
+    }
+}
+
+/// The tokens `text` yields under `config` after all cleaning, transliteration and synonym
+/// replacement, sorted and with repetitions unless `dedupe` is set. These are exactly the
+/// tokens the analyzer counts, so this is the place to look when an expected keyword is
+/// missing. Builds a fresh `Tokenizer`, so reuse one for many texts instead.
+pub fn tokens_of(
+    text: &str,
+    dedupe: bool,
+    config: &TokenizerConfig,
+) -> Result<Vec<String>, regex::Error> {
+    let tokenizer = Tokenizer::new(config)?;
+    Ok(if dedupe {
+        tokenizer.unique_tokens(text)
+    } else {
+        tokenizer.tokenize(text)
+    })
+}
+
+/// Strips leading and trailing hyphens from words longer than four characters.
+pub fn clean_keyword(in_word: String) -> String {
+    let mut ret = in_word;
+    let mut has_changed = true;
+    while has_changed {
+        has_changed = false;
+        if ret.len() > 4 {
+            let first_char = ret.chars().next().unwrap();
+            if first_char == '-' {
+                ret.remove(0);
+                has_changed = true;
+            }
+            let last_char: char = ret.chars().last().unwrap();
+            if last_char == '-' {
+                ret.pop();
+                has_changed = true;
+            }
+        }
+    }
+    ret
+}
+
+/// A crude normalized form of `word` for spotting near-duplicate keywords: hyphens are
+/// removed and common plural endings are stripped. This is no replacement for stemming.
+pub fn variant_key(word: &str) -> String {
+    let mut key: String = word.chars().filter(|c| *c != '-').collect();
+    if let Some(stem) = key.strip_suffix("ies") {
+        key = format!("{}y", stem);
+    } else if key.ends_with('s') && !key.ends_with("ss") && !key.ends_with("us") {
+        key.pop();
+    }
+    key
+}
@@ -0,0 +1,825 @@
+use hcse_analyzer::analyzer_data::AnalyzerData;
+use hcse_analyzer::config::{
+    AnalyzerConfig, CooccurrencePairing, DiagonalCount, PropagationOperator, RowNormalization,
+    TfWeighting,
+};
+use hcse_analyzer::error::AnalyzerError;
+use hcse_analyzer::DEFAULT_HALLMARKS;
+use sprs::CsMat;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[test]
+fn mismatched_relations_matrix_is_reported() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let keywords: Vec<String> = ["apoptosis", "caspase", "telomerase"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+    data.set_relations(CsMat::eye(2));
+
+    let err = data.compute_keyword_ratings().unwrap_err();
+    assert_eq!(
+        err,
+        AnalyzerError::DimensionMismatch {
+            hallmark: DEFAULT_HALLMARKS[0].title,
+            matrix_shape: (2, 2),
+            vector_dim: 3,
+        }
+    );
+    assert!(err.to_string().contains("2x2"));
+}
+
+fn count_co_occurrences(config: &AnalyzerConfig, articles: &[Vec<(&str, usize)>]) -> AnalyzerData {
+    let keywords: Vec<String> = ["apoptosis", "caspase"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let mut data = AnalyzerData::new(keywords.len(), &keywords, config);
+    for article in articles.iter() {
+        let words: Vec<(String, usize)> = article
+            .iter()
+            .map(|(word, count)| (word.to_string(), *count))
+            .collect();
+        data.update_with_article_data(&words);
+    }
+    data
+}
+
+#[test]
+fn binary_co_occurrences_ignore_repetitions() {
+    let config = AnalyzerConfig::default();
+    let repeated = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 3), ("caspase", 2), ("unknown", 1)],
+            vec![("apoptosis", 2)],
+        ],
+    );
+    let unique = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 1), ("caspase", 1), ("unknown", 1)],
+            vec![("apoptosis", 1)],
+        ],
+    );
+    for (a, b) in [
+        ("apoptosis", "apoptosis"),
+        ("apoptosis", "caspase"),
+        ("caspase", "apoptosis"),
+        ("caspase", "caspase"),
+    ] {
+        assert_eq!(repeated.co_occurrence(a, b), unique.co_occurrence(a, b));
+    }
+    assert_eq!(unique.co_occurrence("apoptosis", "apoptosis"), Some(2.0));
+    assert_eq!(unique.co_occurrence("apoptosis", "caspase"), Some(1.0));
+    assert_eq!(unique.co_occurrence("caspase", "caspase"), Some(1.0));
+    assert_eq!(unique.co_occurrence("apoptosis", "unknown"), None);
+}
+
+#[test]
+fn raw_co_occurrences_multiply_the_counts() {
+    let config = AnalyzerConfig {
+        cooccurrence_weighting: TfWeighting::Raw,
+        ..Default::default()
+    };
+    let data = count_co_occurrences(&config, &[vec![("apoptosis", 3), ("caspase", 2)]]);
+    assert_eq!(data.co_occurrence("apoptosis", "caspase"), Some(6.0));
+    assert_eq!(data.co_occurrence("caspase", "apoptosis"), Some(6.0));
+    assert_eq!(data.co_occurrence("apoptosis", "apoptosis"), Some(9.0));
+}
+
+#[test]
+fn minimum_pairing_counts_the_rarer_keyword() {
+    let config = AnalyzerConfig {
+        cooccurrence_weighting: TfWeighting::Raw,
+        cooccurrence_pairing: CooccurrencePairing::Minimum,
+        ..Default::default()
+    };
+    let data = count_co_occurrences(&config, &[vec![("apoptosis", 3), ("caspase", 2)]]);
+    assert_eq!(data.co_occurrence("apoptosis", "caspase"), Some(2.0));
+    assert_eq!(data.co_occurrence("caspase", "apoptosis"), Some(2.0));
+    assert_eq!(data.co_occurrence("apoptosis", "apoptosis"), Some(3.0));
+
+    let binary = AnalyzerConfig {
+        cooccurrence_pairing: CooccurrencePairing::Minimum,
+        ..Default::default()
+    };
+    let data = count_co_occurrences(&binary, &[vec![("apoptosis", 3), ("caspase", 2)]]);
+    assert_eq!(data.co_occurrence("apoptosis", "caspase"), Some(1.0));
+}
+
+#[test]
+fn diagonal_counts_abstracts_or_occurrences_on_request() {
+    let articles = [
+        vec![("apoptosis", 3), ("caspase", 2)],
+        vec![("apoptosis", 1)],
+    ];
+    let diagonal = |diagonal_count| {
+        let config = AnalyzerConfig {
+            cooccurrence_weighting: TfWeighting::Raw,
+            diagonal_count,
+            ..Default::default()
+        };
+        let data = count_co_occurrences(&config, &articles);
+        assert_eq!(data.co_occurrence("apoptosis", "caspase"), Some(6.0));
+        data.co_occurrence("apoptosis", "apoptosis").unwrap()
+    };
+    assert_eq!(diagonal(DiagonalCount::Weighted), 10.0);
+    assert_eq!(diagonal(DiagonalCount::Abstracts), 2.0);
+    assert_eq!(diagonal(DiagonalCount::Occurrences), 4.0);
+}
+
+#[test]
+fn asymmetric_relations_are_reported() {
+    let config = AnalyzerConfig::default();
+    let mut data = count_co_occurrences(&config, &[vec![("apoptosis", 1), ("caspase", 1)]]);
+    assert_eq!(data.check_symmetry(), Ok(()));
+
+    let mut triplets = sprs::TriMat::new((2, 2));
+    triplets.add_triplet(0, 1, 2.0);
+    triplets.add_triplet(1, 0, 1.0);
+    data.set_relations(triplets.to_csr());
+    assert!(matches!(
+        data.check_symmetry(),
+        Err(AnalyzerError::AsymmetricRelations { .. })
+    ));
+}
+
+#[test]
+fn log_normalization_dampens_dominant_keywords_less() {
+    let corpus = [
+        vec![("apoptosis", 1), ("caspase", 1)],
+        vec![("apoptosis", 1)],
+        vec![("apoptosis", 1)],
+        vec![("apoptosis", 1)],
+    ];
+    let mut plain = count_co_occurrences(&AnalyzerConfig::default(), &corpus);
+    plain.divide_rows_by_diagonal();
+    let config = AnalyzerConfig {
+        row_normalization: RowNormalization::LogDiagonal,
+        ..Default::default()
+    };
+    let mut log = count_co_occurrences(&config, &corpus);
+    log.divide_rows_by_diagonal();
+
+    assert_eq!(plain.co_occurrence("apoptosis", "caspase"), Some(0.25));
+    assert_eq!(plain.co_occurrence("caspase", "apoptosis"), Some(1.0));
+    let dominant = log.co_occurrence("apoptosis", "caspase").unwrap();
+    let rare = log.co_occurrence("caspase", "apoptosis").unwrap();
+    assert!((dominant - 1.0 / 5f32.ln()).abs() < 1e-6);
+    assert!((rare - 1.0 / 2f32.ln()).abs() < 1e-6);
+    assert!(rare / dominant < 4.0);
+}
+
+#[test]
+fn incremental_rating_matches_the_batch_rating() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let mut data = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 1), ("caspase", 1)],
+            vec![("apoptosis", 1)],
+        ],
+    );
+    data.divide_rows_by_diagonal();
+    data.compute_keyword_ratings().unwrap();
+
+    let words: Vec<String> = ["apoptosis", "caspase", "apoptosis", "unknown"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let batch = data.rate_article_keywords(words, "PMC1".to_string());
+    let mut accumulator = data.rating_accumulator();
+    accumulator.add_keyword("apoptosis", 1);
+    accumulator.add_keyword("caspase", 1);
+    accumulator.add_keyword("unknown", 1);
+    accumulator.add_keyword("apoptosis", 1);
+    let incremental = accumulator.finalize("PMC1".to_string());
+
+    assert!(batch.is_valid());
+    assert_eq!(incremental.i, batch.i);
+    for (a, b) in incremental.r.iter().zip(batch.r.iter()) {
+        assert!((a - b).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn added_articles_update_the_model_like_a_rebuild() {
+    let weekly: Vec<Vec<(&str, usize)>> = vec![
+        vec![("apoptosis", 2), ("caspase", 1)],
+        vec![("caspase", 1), ("unknown", 3)],
+    ];
+    let initial: Vec<Vec<(&str, usize)>> = vec![
+        vec![("apoptosis", 1), ("caspase", 1)],
+        vec![("apoptosis", 1)],
+    ];
+    let document_frequencies = |articles: &[Vec<(&str, usize)>]| {
+        let mut frequencies = HashMap::new();
+        for (word, _) in articles.iter().flatten() {
+            *frequencies.entry(word.to_string()).or_insert(0) += 1;
+        }
+        frequencies
+    };
+    for row_normalization in [RowNormalization::Diagonal, RowNormalization::Ppmi] {
+        let config = AnalyzerConfig {
+            quiet: true,
+            row_normalization,
+            ..Default::default()
+        };
+        let mut updated = count_co_occurrences(&config, &initial);
+        updated.set_document_frequencies(&document_frequencies(&initial), initial.len());
+        updated.divide_rows_by_diagonal();
+        updated.compute_keyword_ratings().unwrap();
+        let weekly_words: Vec<Vec<(String, usize)>> = weekly
+            .iter()
+            .map(|article| article.iter().map(|(w, c)| (w.to_string(), *c)).collect())
+            .collect();
+        assert_eq!(updated.add_articles(&weekly_words), 2);
+        updated.refinalize().unwrap();
+
+        let all: Vec<Vec<(&str, usize)>> = initial.iter().chain(weekly.iter()).cloned().collect();
+        let mut rebuilt = count_co_occurrences(&config, &all);
+        rebuilt.set_document_frequencies(&document_frequencies(&all), all.len());
+        rebuilt.divide_rows_by_diagonal();
+        rebuilt.compute_keyword_ratings().unwrap();
+
+        assert_eq!(updated.raw_diagonal("caspase"), Some(3.0));
+        assert_eq!(updated.n_documents(), 4);
+        for (a, b) in updated
+            .rating_output()
+            .rating_output
+            .iter()
+            .zip(rebuilt.rating_output().rating_output.iter())
+        {
+            assert_eq!(a.keyword, b.keyword);
+            assert!(a
+                .rating
+                .iter()
+                .zip(b.rating.iter())
+                .all(|(x, y)| (x - y).abs() < 1e-6));
+        }
+    }
+}
+
+#[test]
+fn frequent_new_terms_join_the_vocabulary_on_request() {
+    let weekly: Vec<Vec<(String, usize)>> = [
+        vec!["apoptosis", "necroptosis"],
+        vec!["necroptosis", "caspase", "rare"],
+    ]
+    .iter()
+    .map(|article| article.iter().map(|w| (w.to_string(), 1)).collect())
+    .collect();
+    let initial = [vec![("apoptosis", 1), ("caspase", 1)]];
+
+    let fixed_config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let mut fixed = count_co_occurrences(&fixed_config, &initial);
+    fixed.add_articles(&weekly);
+    assert_eq!(fixed.n_keywords(), 2);
+    assert_eq!(fixed.co_occurrence("apoptosis", "necroptosis"), None);
+
+    let growing_config = AnalyzerConfig {
+        vocabulary_growth: Some(2),
+        ..fixed_config
+    };
+    let mut growing = count_co_occurrences(&growing_config, &initial);
+    growing.divide_rows_by_diagonal();
+    growing.compute_keyword_ratings().unwrap();
+    growing.add_articles(&weekly);
+    assert_eq!(growing.n_keywords(), 3);
+    assert_eq!(growing.keyword_index("necroptosis"), Some(2));
+    assert_eq!(growing.keyword_index("rare"), None);
+    assert_eq!(
+        growing.co_occurrence("necroptosis", "necroptosis"),
+        Some(2.0)
+    );
+    assert_eq!(growing.co_occurrence("apoptosis", "necroptosis"), Some(1.0));
+    assert_eq!(growing.co_occurrence("apoptosis", "apoptosis"), Some(2.0));
+    growing.refinalize().unwrap();
+    assert_eq!(growing.rating_output().rating_output.len(), 3);
+}
+
+#[test]
+fn term_frequencies_are_aligned_to_the_keyword_indices() {
+    let data = count_co_occurrences(&AnalyzerConfig::default(), &[]);
+    let words: Vec<String> = ["caspase", "unknown", "caspase", "apoptosis"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let frequencies = data.term_frequencies(&words);
+
+    assert_eq!(frequencies.dim(), 2);
+    assert_eq!(frequencies.nnz(), 2);
+    assert_eq!(
+        frequencies.get(data.keyword_index("apoptosis").unwrap()),
+        Some(&1.0)
+    );
+    assert_eq!(
+        frequencies.get(data.keyword_index("caspase").unwrap()),
+        Some(&2.0)
+    );
+    assert_eq!(data.term_frequencies(&[]).nnz(), 0);
+}
+
+#[test]
+fn vocab_filter_counts_the_vocabulary_keywords_of_a_text() {
+    let data = count_co_occurrences(&AnalyzerConfig::default(), &[]);
+    let counts = data.vocab_filter("Caspase activation precedes apoptosis, as caspase cleaves.");
+
+    assert_eq!(
+        counts,
+        HashMap::from([("apoptosis".to_string(), 1), ("caspase".to_string(), 2)])
+    );
+    assert!(data.vocab_filter("Unrelated words only.").is_empty());
+}
+
+#[test]
+fn length_normalization_changes_log_weighted_ratings_only() {
+    let apoptosis = 1;
+    let replication = 4;
+    let rate = |tf_weighting: TfWeighting, length_normalization: bool| {
+        let config = AnalyzerConfig {
+            quiet: true,
+            tf_weighting,
+            length_normalization,
+            seed_keywords: HashMap::from([
+                (
+                    DEFAULT_HALLMARKS[apoptosis].title.to_string(),
+                    vec!["seedone".to_string()],
+                ),
+                (
+                    DEFAULT_HALLMARKS[replication].title.to_string(),
+                    vec!["seedtwo".to_string()],
+                ),
+            ]),
+            ..Default::default()
+        };
+        let keywords: Vec<String> = ["seedone", "seedtwo"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+        data.update_with_article_data(&[("seedone".to_string(), 1)]);
+        data.update_with_article_data(&[("seedtwo".to_string(), 1)]);
+        data.divide_rows_by_diagonal();
+        data.compute_keyword_ratings().unwrap();
+        let words: Vec<String> = ["seedone", "seedone", "seedone", "seedtwo", "unknown"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let rating = data.rate_article_keywords(words, "PMC1".to_string());
+        assert!(rating.is_valid());
+        (rating.r[apoptosis], rating.r[replication])
+    };
+    let close =
+        |(a, b): (f32, f32), (x, y): (f32, f32)| (a - x).abs() < 1e-5 && (b - y).abs() < 1e-5;
+    let share = |a: f32, b: f32| (a / (a + b), b / (a + b));
+
+    assert!(close(
+        rate(TfWeighting::Sqrt, true),
+        rate(TfWeighting::Sqrt, false)
+    ));
+    assert!(close(
+        rate(TfWeighting::Log, false),
+        share(3.0f32.ln_1p(), 1.0f32.ln_1p())
+    ));
+    // Shares of the four vocabulary keyword tokens; "unknown" is not one of them.
+    assert!(close(
+        rate(TfWeighting::Log, true),
+        share(0.75f32.ln_1p(), 0.25f32.ln_1p())
+    ));
+}
+
+#[test]
+fn hallmark_subset_ratings_are_renormalized_over_the_subset() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let mut data = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 1), ("caspase", 1)],
+            vec![("apoptosis", 1)],
+        ],
+    );
+    data.divide_rows_by_diagonal();
+    data.compute_keyword_ratings().unwrap();
+    let words = || vec!["apoptosis".to_string(), "caspase".to_string()];
+    let full = data.rate_article_keywords(words(), "PMC1".to_string());
+    let rated: Vec<usize> = (0..DEFAULT_HALLMARKS.len())
+        .filter(|h| full.r[*h] > 0.0)
+        .collect();
+    assert!(rated.len() >= 2, "{:?}", full.r);
+
+    let subset = [rated[0], rated[1]];
+    let restricted = data.rate_article_keywords_for_hallmarks(words(), "PMC1".to_string(), &subset);
+    assert!(restricted.is_valid());
+    for (hallmark, value) in restricted.r.iter().enumerate() {
+        if subset.contains(&hallmark) {
+            let expected = full.r[hallmark] / (full.r[rated[0]] + full.r[rated[1]]);
+            assert!((value - expected).abs() < 1e-6);
+        } else {
+            assert_eq!(*value, 0.0);
+        }
+    }
+}
+
+#[test]
+fn top_pairs_skip_the_diagonal_unless_requested() {
+    let config = AnalyzerConfig::default();
+    let data = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 1), ("caspase", 1)],
+            vec![("apoptosis", 1)],
+        ],
+    );
+    let pair = |a: &str, b: &str, value| (a.to_string(), b.to_string(), value);
+    assert_eq!(
+        data.top_pairs(5, false),
+        vec![
+            pair("apoptosis", "caspase", 1.0),
+            pair("caspase", "apoptosis", 1.0)
+        ]
+    );
+    assert_eq!(
+        data.top_pairs(1, true),
+        vec![pair("apoptosis", "apoptosis", 2.0)]
+    );
+}
+
+#[test]
+fn neighbors_rank_the_partners_of_a_keyword() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let keywords: Vec<String> = ["metastasis", "invasion", "migration", "telomerase"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+    for words in [
+        vec!["metastasis", "invasion"],
+        vec!["metastasis", "invasion", "migration"],
+        vec!["metastasis", "migration"],
+        vec!["metastasis", "invasion"],
+        vec!["telomerase"],
+    ] {
+        let words: Vec<(String, usize)> = words.iter().map(|w| (w.to_string(), 1)).collect();
+        data.update_with_article_data(&words);
+    }
+
+    let raw = vec![
+        ("invasion".to_string(), 3.0),
+        ("migration".to_string(), 2.0),
+    ];
+    assert_eq!(data.neighbors("metastasis", 5, false), raw);
+    assert_eq!(data.neighbors("metastasis", 1, false), raw[..1]);
+    data.divide_rows_by_diagonal();
+    assert_eq!(data.neighbors("metastasis", 5, true), raw);
+    assert_eq!(
+        data.neighbors("metastasis", 5, false),
+        vec![
+            ("invasion".to_string(), 0.75),
+            ("migration".to_string(), 0.5)
+        ]
+    );
+    assert!(data.neighbors("telomerase", 5, false).is_empty());
+    assert!(data.neighbors("unknown", 5, false).is_empty());
+}
+
+#[test]
+fn top_lists_break_score_ties_by_keyword() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let keywords: Vec<String> = ["delta", "alpha", "charlie", "bravo"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+    let words: Vec<(String, usize)> = keywords.iter().map(|k| (k.clone(), 1)).collect();
+    data.update_with_article_data(&words);
+
+    let neighbors: Vec<String> = data
+        .neighbors("charlie", 3, false)
+        .into_iter()
+        .map(|(keyword, _)| keyword)
+        .collect();
+    assert_eq!(neighbors, vec!["alpha", "bravo", "delta"]);
+    let pairs: Vec<(String, String)> = data
+        .top_pairs(3, false)
+        .into_iter()
+        .map(|(a, b, _)| (a, b))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("alpha".to_string(), "bravo".to_string()),
+            ("alpha".to_string(), "charlie".to_string()),
+            ("alpha".to_string(), "delta".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn keyword_index_describes_the_matrix_entries() {
+    let config = AnalyzerConfig::default();
+    let data = count_co_occurrences(
+        &config,
+        &[vec![("apoptosis", 1), ("caspase", 1)], vec![("caspase", 1)]],
+    );
+    let mut index = vec![];
+    data.write_keyword_index_to(&mut index).unwrap();
+    let keywords: Vec<String> = String::from_utf8(index)
+        .unwrap()
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (position, keyword) = line.split_once('\t').unwrap();
+            assert_eq!(position, i.to_string());
+            keyword.to_string()
+        })
+        .collect();
+    assert_eq!(keywords.len(), 2);
+    for (i, j, value) in data.relation_entries(true) {
+        assert_eq!(data.co_occurrence(&keywords[i], &keywords[j]), Some(value));
+    }
+}
+
+#[test]
+fn ppmi_rewards_surprising_pairs_and_clamps_negative_values() {
+    let config = AnalyzerConfig {
+        row_normalization: RowNormalization::Ppmi,
+        ..Default::default()
+    };
+    let corpus = [
+        vec![("apoptosis", 1), ("caspase", 1)],
+        vec![("apoptosis", 1)],
+        vec![("caspase", 1)],
+    ];
+    let frequencies = HashMap::from([("apoptosis".to_string(), 2), ("caspase".to_string(), 2)]);
+
+    let mut sparse = count_co_occurrences(&config, &corpus);
+    sparse.set_document_frequencies(&frequencies, 8);
+    sparse.divide_rows_by_diagonal();
+    let pair = sparse.co_occurrence("apoptosis", "caspase").unwrap();
+    assert!((pair - 2f32.ln()).abs() < 1e-6);
+    assert_eq!(sparse.co_occurrence("caspase", "apoptosis"), Some(pair));
+    let own = sparse.co_occurrence("apoptosis", "apoptosis").unwrap();
+    assert!((own - 4f32.ln()).abs() < 1e-6);
+
+    let mut dense = count_co_occurrences(&config, &corpus);
+    dense.set_document_frequencies(&frequencies, 3);
+    dense.divide_rows_by_diagonal();
+    assert_eq!(dense.co_occurrence("apoptosis", "caspase"), Some(0.0));
+}
+
+#[test]
+fn raw_counts_survive_normalization() {
+    let config = AnalyzerConfig::default();
+    let mut data = count_co_occurrences(
+        &config,
+        &[
+            vec![("apoptosis", 1), ("caspase", 1)],
+            vec![("apoptosis", 1)],
+        ],
+    );
+    assert_eq!(data.raw_diagonal("apoptosis"), None);
+    data.divide_rows_by_diagonal();
+
+    assert_eq!(data.co_occurrence("apoptosis", "apoptosis"), Some(1.0));
+    assert_eq!(data.raw_diagonal("apoptosis"), Some(2.0));
+    assert_eq!(data.raw_diagonal("caspase"), Some(1.0));
+    assert_eq!(data.raw_row_sum("apoptosis"), Some(3.0));
+    assert_eq!(data.raw_row_sum("unknown"), None);
+}
+
+#[test]
+fn connectivity_counts_components_and_isolated_keywords() {
+    let config = AnalyzerConfig::default();
+    let keywords: Vec<String> = ["apoptosis", "caspase", "cytochrome", "telomerase"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+    for article in [
+        vec!["apoptosis", "caspase"],
+        vec!["caspase", "cytochrome"],
+        vec!["telomerase"],
+    ] {
+        let words: Vec<(String, usize)> = article.iter().map(|w| (w.to_string(), 1)).collect();
+        data.update_with_article_data(&words);
+    }
+
+    let connectivity = data.connectivity();
+    assert_eq!(connectivity.n_components, 2);
+    assert_eq!(connectivity.n_isolated, 1);
+    assert_eq!(connectivity.degree_histogram, vec![(0, 1), (1, 2), (2, 1)]);
+    assert_eq!(connectivity.median_degree(), 1);
+    assert_eq!(connectivity.max_degree(), 2);
+}
+
+#[test]
+fn propagation_operator_decides_where_ratings_flow() {
+    let apoptosis = 1;
+    let replication = 4;
+    let partner_rating = |propagation| {
+        let config = AnalyzerConfig {
+            quiet: true,
+            propagation,
+            seed_keywords: HashMap::from([
+                (
+                    DEFAULT_HALLMARKS[apoptosis].title.to_string(),
+                    vec!["seedone".to_string()],
+                ),
+                (
+                    DEFAULT_HALLMARKS[replication].title.to_string(),
+                    vec!["seedtwo".to_string()],
+                ),
+            ]),
+            ..Default::default()
+        };
+        let keywords: Vec<String> = ["partner", "seedone", "seedtwo"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let mut data = AnalyzerData::new(keywords.len(), &keywords, &config);
+        let abstracts = [
+            vec!["seedone", "partner"],
+            vec!["seedtwo", "partner"],
+            vec!["seedone"],
+            vec!["seedone"],
+            vec!["seedone"],
+        ];
+        for words in abstracts.iter() {
+            let words: Vec<(String, usize)> = words.iter().map(|w| (w.to_string(), 1)).collect();
+            data.update_with_article_data(&words);
+        }
+        data.divide_rows_by_diagonal();
+        data.compute_keyword_ratings().unwrap();
+        let output = data.rating_output();
+        let partner = output
+            .rating_output
+            .iter()
+            .find(|r| r.keyword == "partner")
+            .unwrap();
+        (partner.rating[apoptosis], partner.rating[replication])
+    };
+    let close =
+        |(a, b): (f32, f32), (x, y): (f32, f32)| (a - x).abs() < 1e-6 && (b - y).abs() < 1e-6;
+
+    assert!(close(partner_rating(PropagationOperator::Rows), (0.5, 0.5)));
+    assert!(close(
+        partner_rating(PropagationOperator::Transposed),
+        (0.2, 0.8)
+    ));
+    assert!(close(
+        partner_rating(PropagationOperator::Symmetrized),
+        (1.0 / 3.0, 2.0 / 3.0)
+    ));
+}
+
+#[test]
+fn fixed_idf_table_replaces_the_corpus_document_frequencies() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        idf_weighting: true,
+        idf_table: Some(HashMap::from([("apoptosis".to_string(), 3.5)])),
+        default_idf: 2.0,
+        ..Default::default()
+    };
+    let corpus = [
+        vec![("apoptosis", 1), ("caspase", 1)],
+        vec![("apoptosis", 1)],
+    ];
+    let mut data = count_co_occurrences(&config, &corpus);
+    data.set_document_frequencies(
+        &HashMap::from([("apoptosis".to_string(), 2), ("caspase".to_string(), 1)]),
+        4,
+    );
+    let apoptosis = data.keyword_index("apoptosis").unwrap();
+    let caspase = data.keyword_index("caspase").unwrap();
+    assert!((data.inverse_document_frequency(caspase) - (4f32.ln() + 1.0)).abs() < 1e-6);
+
+    data.divide_rows_by_diagonal();
+    data.compute_keyword_ratings().unwrap();
+    assert_eq!(data.inverse_document_frequency(apoptosis), 3.5);
+    assert_eq!(data.inverse_document_frequency(caspase), 2.0);
+}
+
+#[test]
+fn jaccard_divides_shared_abstracts_by_all_abstracts_of_a_pair() {
+    let config = AnalyzerConfig::default();
+    let corpus = [
+        vec![("apoptosis", 1), ("caspase", 1)],
+        vec![("apoptosis", 2)],
+        vec![("apoptosis", 1)],
+        vec![("caspase", 1)],
+    ];
+    let mut data = count_co_occurrences(&config, &corpus);
+    data.set_document_frequencies(
+        &HashMap::from([("apoptosis".to_string(), 3), ("caspase".to_string(), 2)]),
+        corpus.len(),
+    );
+    assert_eq!(data.jaccard("apoptosis", "caspase"), Some(0.25));
+    assert_eq!(data.jaccard("apoptosis", "apoptosis"), Some(1.0));
+    assert_eq!(data.jaccard("apoptosis", "unknown"), None);
+
+    data.divide_rows_by_diagonal();
+    assert_eq!(data.jaccard("caspase", "apoptosis"), Some(0.25));
+}
+
+#[test]
+fn non_finite_ratings_are_located() {
+    let config = AnalyzerConfig {
+        quiet: true,
+        ..Default::default()
+    };
+    let mut data = count_co_occurrences(&config, &[vec![("apoptosis", 1), ("caspase", 1)]]);
+    data.set_relations(CsMat::new(
+        (2, 2),
+        vec![0, 2, 4],
+        vec![0, 1, 0, 1],
+        vec![f32::NAN, 1.0, 1.0, 1.0],
+    ));
+    data.compute_keyword_ratings().unwrap();
+
+    let non_finite = data.non_finite_ratings();
+    assert!(!non_finite.is_empty());
+    assert!(non_finite
+        .iter()
+        .all(|(keyword, _, value)| keyword == "apoptosis" && value.is_nan()));
+    assert!(data.rating_normalization_violations().is_empty());
+}
+
+/// Times `normalize_keyword_rating` in both layouts on 20000 keywords with about 10
+/// partners each, once on the dense ratings of a new model, which seeding changes in only a
+/// few entries, and once after propagation. Run with
+/// `cargo test --release --test analyzer_data -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn benchmark_keyword_rating_normalization_layouts() {
+    let n_keywords = 20000;
+    let n_repetitions = 20;
+    let keywords: Vec<String> = (0..n_keywords).map(|i| format!("k{}", i)).collect();
+    let build = |keyword_major_normalization: bool| {
+        let config = AnalyzerConfig {
+            quiet: true,
+            keyword_major_normalization,
+            seed_keywords: DEFAULT_HALLMARKS
+                .iter()
+                .enumerate()
+                .map(|(h, hallmark)| (hallmark.title.to_string(), vec![format!("k{}", h * 1000)]))
+                .collect(),
+            ..Default::default()
+        };
+        let mut data = AnalyzerData::new(n_keywords, &keywords, &config);
+        for i in 0..n_keywords {
+            let words: Vec<(String, usize)> = (0..6)
+                .map(|offset| (keywords[(i + offset) % n_keywords].clone(), 1))
+                .collect();
+            data.update_with_article_data(&words);
+        }
+        data.divide_rows_by_diagonal();
+        data
+    };
+    let time = |data: &mut AnalyzerData| {
+        let mut times: Vec<Duration> = (0..n_repetitions)
+            .map(|_| {
+                let start = Instant::now();
+                data.normalize_keyword_rating();
+                start.elapsed()
+            })
+            .collect();
+        times.sort();
+        times[n_repetitions / 2]
+    };
+
+    for (layout, keyword_major_normalization) in
+        [("hallmark-major", false), ("keyword-major", true)]
+    {
+        let mut data = build(keyword_major_normalization);
+        let seeded = time(&mut data);
+        data.compute_keyword_ratings().unwrap();
+        let propagated = time(&mut data);
+        println!(
+            "{}: median {:?} as seeded, {:?} after propagation",
+            layout, seeded, propagated
+        );
+    }
+}
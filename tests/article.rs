@@ -0,0 +1,60 @@
+use hcse_analyzer::article::{read_articles, InputSource};
+use hcse_analyzer::error::InputError;
+use std::fs;
+
+#[test]
+fn parse_errors_name_the_file_and_the_article() {
+    let path = std::env::temp_dir().join(format!("hcse_malformed_{}.json", std::process::id()));
+    let article =
+        r#"{"title": "", "pmid": "", "doi": "", "pmc": "PMC1", "pii": "", "paper_abstract": ""}"#;
+    let truncated = r#"{"title": "", "pmid": "", "doi": "", "pmc": "PMC2"}"#;
+    fs::write(&path, format!("[{}, {}, {}]", article, article, truncated)).unwrap();
+    let filename = path.to_string_lossy().to_string();
+
+    let err = read_articles(&filename).unwrap_err();
+    assert!(matches!(
+        err,
+        InputError::Parse {
+            article: Some(2),
+            ..
+        }
+    ));
+    let message = err.to_string();
+    assert!(message.starts_with(&format!("file {}, article 2: ", filename)));
+    assert!(message.contains("missing field"));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn malformed_articles_can_be_skipped() {
+    let path = std::env::temp_dir().join(format!("hcse_lenient_{}.json", std::process::id()));
+    let article =
+        r#"{"title": "", "pmid": "", "doi": "", "pmc": "PMC1", "pii": "", "paper_abstract": ""}"#;
+    fs::write(&path, format!("[{}, {{\"pmc\": 2}}, {}]", article, article)).unwrap();
+    let source = InputSource {
+        files: vec![path.to_string_lossy().to_string()],
+    };
+
+    assert!(source.read().is_err());
+    let (articles, skipped) = source.read_skipping_malformed().unwrap();
+    assert_eq!(articles.len(), 2);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].index, 1);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn common_field_spellings_are_accepted() {
+    let path = std::env::temp_dir().join(format!("hcse_aliases_{}.json", std::process::id()));
+    let article = r#"{"Title": "T", "PMID": "1", "DOI": "d", "pmcid": "PMC1", "PII": "", "abstract": "Tumor growth."}"#;
+    fs::write(&path, format!("[{}]", article)).unwrap();
+
+    let articles = read_articles(&path.to_string_lossy()).unwrap();
+    assert_eq!(articles[0].pmc, "PMC1");
+    assert_eq!(articles[0].pmid, "1");
+    assert_eq!(articles[0].paper_abstract, "Tumor growth.");
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,93 @@
+use hcse_analyzer::analyzer::PublicationRater;
+use hcse_analyzer::config::{AnalyzerConfig, HallmarkMismatch};
+use hcse_analyzer::ensemble::{Ensemble, RatingModel};
+use hcse_analyzer::DEFAULT_HALLMARKS;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+fn write_database(name: &str, database: &Value) -> String {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("hcse_{}_{}.json", name, std::process::id()));
+    fs::write(&path, database.to_string()).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn two_hallmark_database() -> Value {
+    json!({
+        "format_version": 1,
+        "hallmarks": [
+            {"title": "Growth", "description": ""},
+            {"title": "Death", "description": ""},
+        ],
+        "rating_output": [
+            {"keyword": "apoptosis", "rating": [0.25, 0.75]},
+        ],
+    })
+}
+
+#[test]
+fn other_hallmarks_are_refused_or_adopted() {
+    let path = write_database("two_hallmarks", &two_hallmark_database());
+
+    let err = RatingModel::load(&path).err().unwrap();
+    assert!(err.to_string().contains("Growth, Death"), "{}", err);
+
+    let model = RatingModel::load_with(&path, HallmarkMismatch::Adapt).unwrap();
+    assert_eq!(model.hallmarks(), ["Growth", "Death"]);
+    let config = AnalyzerConfig {
+        hallmark_mismatch: HallmarkMismatch::Adapt,
+        ..Default::default()
+    };
+    let ensemble = Ensemble::load(std::slice::from_ref(&path), &config).unwrap();
+    let rated = ensemble.rate_article_keywords(vec!["apoptosis".to_string()], "PMC1".to_string());
+    assert_eq!(rated.r, vec![0.25, 0.75]);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn ensemble_models_need_the_same_hallmarks() {
+    let other = write_database("two_hallmarks_ensemble", &two_hallmark_database());
+    let mut rating = vec![0.0; DEFAULT_HALLMARKS.len()];
+    rating[0] = 1.0;
+    let legacy = write_database(
+        "legacy_hallmarks",
+        &json!({"rating_output": [{"keyword": "apoptosis", "rating": rating}]}),
+    );
+
+    assert_eq!(
+        RatingModel::load(&legacy).unwrap().hallmarks().len(),
+        DEFAULT_HALLMARKS.len()
+    );
+    let paths = [legacy.clone(), other.clone()];
+    let config = AnalyzerConfig {
+        hallmark_mismatch: HallmarkMismatch::Adapt,
+        ..Default::default()
+    };
+    let err = Ensemble::load(&paths, &config).err().unwrap();
+    assert!(err.to_string().contains("other hallmarks"), "{}", err);
+
+    fs::remove_file(&other).unwrap();
+    fs::remove_file(&legacy).unwrap();
+}
+
+#[test]
+fn hallmarks_are_found_by_title_and_index() {
+    let title = DEFAULT_HALLMARKS[3].title;
+    assert_eq!(hcse_analyzer::hallmark_index(title), Some(3));
+    assert_eq!(
+        hcse_analyzer::hallmark_by_title(title).unwrap().title,
+        title
+    );
+    assert_eq!(hcse_analyzer::hallmark_at(3).unwrap().title, title);
+    assert_eq!(hcse_analyzer::hallmark_index("Growth"), None);
+    assert!(hcse_analyzer::hallmark_at(DEFAULT_HALLMARKS.len()).is_none());
+
+    let path = write_database("two_hallmarks_lookup", &two_hallmark_database());
+    let model = RatingModel::load_with(&path, HallmarkMismatch::Adapt).unwrap();
+    assert_eq!(model.hallmark_index("Death"), Some(1));
+    assert_eq!(model.hallmark_index(title), None);
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,953 @@
+use hcse_analyzer::analyzer::{Analyzer, ArticleDatabase, RatedPublication};
+use hcse_analyzer::config::{AnalyzerConfig, DiagonalCount, HallmarkMismatch, TfWeighting};
+use hcse_analyzer::ensemble::RatingModel;
+use hcse_analyzer::error::{AnalyzerError, InputError};
+use hcse_analyzer::output::FORMAT_VERSION;
+use hcse_analyzer::publication_report::{PublicationReport, ReportFormat};
+use hcse_analyzer::rating_validation::ValidationReport;
+use hcse_analyzer::synthetic::SyntheticCorpus;
+use hcse_analyzer::{default_hallmark_titles, DEFAULT_HALLMARKS};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+fn fixture_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("hcse_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn article(pmc: &str, paper_abstract: &str) -> Value {
+    json!({
+        "title": "",
+        "pmid": "",
+        "doi": "",
+        "pmc": pmc,
+        "pii": "",
+        "paper_abstract": paper_abstract,
+    })
+}
+
+fn write_files(dir: &Path, files: &[Vec<Value>]) -> Vec<String> {
+    let mut filenames = vec![];
+    for (i, articles) in files.iter().enumerate() {
+        let path = dir.join(format!("results_{}.json", i));
+        fs::write(&path, serde_json::to_string(articles).unwrap()).unwrap();
+        filenames.push(path.to_string_lossy().to_string());
+    }
+    filenames
+}
+
+fn write_corpus(dir: &Path) -> Vec<String> {
+    write_files(
+        dir,
+        &[
+            vec![
+                article("PMC1", "Angiogenesis requires vascular endothelial growth."),
+                article("PMC2", "Telomerase maintains telomeres during replication."),
+            ],
+            vec![
+                article("PMC3", "Apoptotic caspase cascades release cytochrome."),
+                article("", "Angiogenesis and telomerase interplay."),
+            ],
+        ],
+    )
+}
+
+fn pipeline_config(dir: &Path, input_files: Vec<String>) -> AnalyzerConfig {
+    AnalyzerConfig {
+        lower_cutoff: 0.0,
+        upper_cutoff: 10.0,
+        input_files,
+        rating_output_path: dir
+            .join("rating_database.json")
+            .to_string_lossy()
+            .to_string(),
+        article_output_path: dir
+            .join("article_database.json")
+            .to_string_lossy()
+            .to_string(),
+        quiet: true,
+        ..Default::default()
+    }
+}
+
+fn run_pipeline(dir: &Path, config: AnalyzerConfig) -> (Value, Value) {
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.run().unwrap();
+    let read = |name: &str| -> Value {
+        serde_json::from_str(&fs::read_to_string(dir.join(name)).unwrap()).unwrap()
+    };
+    let (rating_database, article_database) =
+        (read("rating_database.json"), read("article_database.json"));
+    assert_eq!(rating_database["format_version"], FORMAT_VERSION);
+    assert_eq!(article_database["format_version"], FORMAT_VERSION);
+    (rating_database, article_database["publications"].clone())
+}
+
+fn keywords(rating_database: &Value) -> Vec<&str> {
+    rating_database["rating_output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["keyword"].as_str().unwrap())
+        .collect()
+}
+
+fn parse_rating(rating: &Value) -> Vec<f32> {
+    rating
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| match r {
+            Value::String(s) => s.parse().unwrap(),
+            other => other.as_f64().unwrap() as f32,
+        })
+        .collect()
+}
+
+fn dominant_hallmark(rating: &[f32]) -> &'static str {
+    let best = rating
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap()
+        .0;
+    DEFAULT_HALLMARKS[best].title
+}
+
+#[test]
+fn full_run_writes_consistent_databases() {
+    let dir = fixture_dir("pipeline");
+    let config = pipeline_config(&dir, write_corpus(&dir));
+    let (rating_database, article_database) = run_pipeline(&dir, config);
+
+    let hallmarks = rating_database["hallmarks"].as_array().unwrap();
+    assert_eq!(hallmarks.len(), DEFAULT_HALLMARKS.len());
+
+    assert_eq!(
+        keywords(&rating_database),
+        vec![
+            "angiogenesis",
+            "apoptotic",
+            "cascades",
+            "caspase",
+            "cytochrome",
+            "during",
+            "endothelial",
+            "growth",
+            "interplay",
+            "maintains",
+            "release",
+            "replication",
+            "requires",
+            "telomerase",
+            "telomeres",
+            "vascular",
+        ]
+    );
+    for entry in rating_database["rating_output"].as_array().unwrap() {
+        let rating = parse_rating(&entry["rating"]);
+        assert_eq!(rating.len(), DEFAULT_HALLMARKS.len());
+        let sum: f32 = rating.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-3, "{}: {}", entry["keyword"], sum);
+    }
+
+    let publications = article_database.as_array().unwrap();
+    let ids: Vec<&str> = publications
+        .iter()
+        .map(|p| p["i"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["PMC1", "PMC2", "PMC3"]);
+    let ratings: Vec<Vec<f32>> = publications.iter().map(|p| parse_rating(&p["r"])).collect();
+    for rating in ratings.iter() {
+        let sum: f32 = rating.iter().sum();
+        assert!((sum - 1.0).abs() < 0.01);
+    }
+    assert_eq!(dominant_hallmark(&ratings[0]), "Sustained angiogenesis");
+    assert_eq!(
+        dominant_hallmark(&ratings[1]),
+        "Limitless replicative potential"
+    );
+    assert_eq!(dominant_hallmark(&ratings[2]), "Evading apoptosis");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn keyword_pattern_focuses_the_rating_database() {
+    let dir = fixture_dir("keyword_pattern");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.output_keyword_pattern = Some("^telo".to_string());
+    let (rating_database, article_database) = run_pipeline(&dir, config);
+
+    assert_eq!(keywords(&rating_database), vec!["telomerase", "telomeres"]);
+    assert_eq!(article_database.as_array().unwrap().len(), 3);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn confidence_is_written_on_request() {
+    let dir = fixture_dir("confidence");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    let (_, article_database) = run_pipeline(&dir, config.clone());
+    assert!(article_database[0].get("c").is_none());
+
+    config.publication_confidence = true;
+    let (_, article_database) = run_pipeline(&dir, config);
+    for publication in article_database.as_array().unwrap() {
+        let confidence: f32 = publication["c"].as_str().unwrap().parse().unwrap();
+        assert!((0.0..=1.0).contains(&confidence), "{}", confidence);
+    }
+
+    let n = DEFAULT_HALLMARKS.len();
+    let mut specific = vec![0.0; n];
+    specific[0] = 1.0;
+    let rated = |r: Vec<f32>| RatedPublication {
+        i: "PMC1".to_string(),
+        r,
+        c: None,
+    };
+    assert_eq!(rated(specific).confidence(), 1.0);
+    assert!(rated(vec![1.0 / n as f32; n]).confidence().abs() < 1e-6);
+    assert_eq!(rated(vec![1.0]).confidence(), 1.0);
+    assert_eq!(rated(vec![]).confidence(), 1.0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Asserts that two runs wrote the same keyword ratings and, up to their rounding, the same
+/// publication ratings.
+fn assert_same_model(a: &(Value, Value), b: &(Value, Value)) {
+    assert_eq!(keywords(&a.0), keywords(&b.0));
+    let ratings = |database: &Value| -> Vec<Vec<f32>> {
+        database["rating_output"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| parse_rating(&r["rating"]))
+            .collect()
+    };
+    for (rating_a, rating_b) in ratings(&a.0).iter().zip(ratings(&b.0)) {
+        assert!(rating_a
+            .iter()
+            .zip(rating_b.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-6));
+    }
+    let publications = |database: &Value| -> Vec<(String, Vec<f32>)> {
+        database
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (p["i"].as_str().unwrap().to_string(), parse_rating(&p["r"])))
+            .collect()
+    };
+    for (publication_a, publication_b) in publications(&a.1).iter().zip(publications(&b.1)) {
+        assert_eq!(publication_a.0, publication_b.0);
+        // Written publication ratings are rounded to three decimals.
+        assert!(publication_a
+            .1
+            .iter()
+            .zip(publication_b.1.iter())
+            .all(|(x, y)| (x - y).abs() <= 1.5e-3));
+    }
+}
+
+#[test]
+fn cached_tokens_build_the_same_model() {
+    let dir = fixture_dir("cache_tokens");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    let uncached = run_pipeline(&dir, config.clone());
+    config.cache_tokens = true;
+    let cached = run_pipeline(&dir, config);
+
+    assert_same_model(&cached, &uncached);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn single_pass_builds_the_same_model() {
+    let dir = fixture_dir("single_pass");
+    let two_pass_config = pipeline_config(&dir, write_corpus(&dir));
+    for diagonal_count in [DiagonalCount::Weighted, DiagonalCount::Occurrences] {
+        let mut config = two_pass_config.clone();
+        config.diagonal_count = diagonal_count;
+        let two_pass = run_pipeline(&dir, config.clone());
+        config.single_pass = true;
+        let single_pass = run_pipeline(&dir, config);
+
+        assert_same_model(&single_pass, &two_pass);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn keyword_major_normalization_gives_the_same_ratings() {
+    let dir = fixture_dir("keyword_major");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    let hallmark_major = run_pipeline(&dir, config.clone());
+    config.keyword_major_normalization = true;
+    let keyword_major = run_pipeline(&dir, config);
+
+    assert_eq!(keyword_major, hallmark_major);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn articles_with_too_few_keywords_are_skipped() {
+    let dir = fixture_dir("min_relevant_keywords");
+    let files = write_files(
+        &dir,
+        &[vec![
+            article("PMC1", "Angiogenesis requires vascular endothelial growth."),
+            article("PMC2", "Telomerase maintains telomeres during replication."),
+            article("PMC3", "Apoptosis."),
+        ]],
+    );
+    let mut config = pipeline_config(&dir, files);
+    config.min_relevant_keywords = 2;
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.run().unwrap();
+
+    let article_database: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("article_database.json")).unwrap())
+            .unwrap();
+    let ids: Vec<&str> = article_database["publications"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["i"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["PMC1", "PMC2"]);
+    let summary = analyzer.summary();
+    assert_eq!(summary.n_sparse_articles, 1);
+    assert_eq!(summary.corpus_distribution.dropped.too_few_keywords, 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn short_abstracts_are_skipped_in_every_phase() {
+    let dir = fixture_dir("min_abstract_length");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.min_abstract_length = 40;
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.run().unwrap();
+
+    let article_database: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("article_database.json")).unwrap())
+            .unwrap();
+    let ids: Vec<&str> = article_database["publications"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["i"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["PMC1", "PMC2", "PMC3"]);
+    assert_eq!(analyzer.summary().n_short_articles, 1);
+    let rating_database: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("rating_database.json")).unwrap())
+            .unwrap();
+    assert!(!keywords(&rating_database).contains(&"interplay"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn synonyms_collapse_aliases_across_the_pipeline() {
+    let dir = fixture_dir("synonyms");
+    let files = write_files(
+        &dir,
+        &[vec![
+            article("PMC1", "Neoplasm growth requires angiogenesis."),
+            article("PMC2", "Tumour growth requires angiogenesis."),
+        ]],
+    );
+    let mut config = pipeline_config(&dir, files);
+    config.tokenizer.synonyms = HashMap::from([
+        ("neoplasm".to_string(), "tumor".to_string()),
+        ("tumour".to_string(), "tumor".to_string()),
+    ]);
+    config.seed_keywords = HashMap::from([(
+        DEFAULT_HALLMARKS[0].title.to_string(),
+        vec!["neoplasm".to_string()],
+    )]);
+    let (rating_database, article_database) = run_pipeline(&dir, config);
+
+    assert_eq!(
+        keywords(&rating_database),
+        vec!["angiogenesis", "growth", "requires", "tumor"]
+    );
+    let tumor = rating_database["rating_output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["keyword"] == "tumor")
+        .unwrap();
+    assert!(parse_rating(&tumor["rating"])[0] > 0.0);
+
+    let ratings: Vec<Vec<f32>> = article_database
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| parse_rating(&p["r"]))
+        .collect();
+    assert_eq!(ratings.len(), 2);
+    assert_eq!(ratings[0], ratings[1]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn progress_callback_reaches_the_total_of_every_phase() {
+    let dir = fixture_dir("progress");
+    let config = pipeline_config(&dir, write_corpus(&dir));
+    let events = Arc::new(Mutex::new(vec![]));
+    let recorder = events.clone();
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.set_progress_callback(move |phase, current, total| {
+        recorder
+            .lock()
+            .unwrap()
+            .push((phase.to_string(), current, total));
+    });
+    analyzer.run().unwrap();
+
+    let events = events.lock().unwrap();
+    let mut phases: Vec<&str> = events.iter().map(|e| e.0.as_str()).collect();
+    phases.dedup();
+    assert_eq!(phases.len(), 3);
+    for phase in phases {
+        let last = events.iter().rfind(|e| e.0 == phase).unwrap();
+        assert_eq!((last.1, last.2), (2, 2), "{}", phase);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn article_progress_reuses_the_counts_of_the_candidate_scan() {
+    let dir = fixture_dir("article_progress");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.article_progress = true;
+    let events = Arc::new(Mutex::new(vec![]));
+    let recorder = events.clone();
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.set_progress_callback(move |phase, current, total| {
+        recorder
+            .lock()
+            .unwrap()
+            .push((phase.to_string(), current, total));
+    });
+    analyzer.run().unwrap();
+
+    let events = events.lock().unwrap();
+    let mut phases: Vec<&str> = events.iter().map(|e| e.0.as_str()).collect();
+    phases.dedup();
+    let totals: Vec<(u64, u64)> = phases
+        .iter()
+        .map(|phase| {
+            let last = events.iter().rfind(|e| e.0 == *phase).unwrap();
+            (last.1, last.2)
+        })
+        .collect();
+    assert_eq!(totals, vec![(2, 2), (4, 4), (4, 4)]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn article_databases_load_with_and_without_a_format_version() {
+    let dir = fixture_dir("format_version");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.stream_article_output = true;
+    Analyzer::with_config(config.clone()).run().unwrap();
+    let streamed = ArticleDatabase::load(&config.article_output_path).unwrap();
+    assert_eq!(streamed.format_version, FORMAT_VERSION);
+    assert_eq!(streamed.hallmarks, default_hallmark_titles());
+    let ids: Vec<&str> = streamed.publications.iter().map(|p| p.i.as_str()).collect();
+    assert_eq!(ids, vec!["PMC1", "PMC2", "PMC3"]);
+
+    let legacy = dir.join("legacy.json");
+    fs::write(&legacy, r#"[{"i": "PMC1", "r": ["0.250", "0.750"]}]"#).unwrap();
+    let legacy = ArticleDatabase::load(&legacy.to_string_lossy()).unwrap();
+    assert_eq!(legacy.format_version, 0);
+    assert_eq!(legacy.hallmarks, default_hallmark_titles());
+    assert_eq!(legacy.publications[0].r, vec![0.25, 0.75]);
+
+    let newer = dir.join("newer.json");
+    fs::write(&newer, r#"{"format_version": 1000, "publications": []}"#).unwrap();
+    assert!(ArticleDatabase::load(&newer.to_string_lossy()).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn article_id_policy_decides_which_articles_are_rated() {
+    let dir = fixture_dir("article_id");
+    let config = pipeline_config(&dir, write_corpus(&dir));
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.set_article_id(|article| match article.pmc.as_str() {
+        "PMC2" => None,
+        "" => Some("untitled".to_string()),
+        pmc => Some(format!("pmc:{}", pmc)),
+    });
+    analyzer.run().unwrap();
+
+    let article_database: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("article_database.json")).unwrap())
+            .unwrap();
+    let ids: Vec<&str> = article_database["publications"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["i"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["pmc:PMC1", "pmc:PMC3", "untitled"]);
+    assert_eq!(
+        analyzer.summary().corpus_distribution.dropped.without_pmc,
+        1
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn synthetic_corpus_is_reproducible_and_fully_rated() {
+    let corpus = SyntheticCorpus {
+        n_files: 10,
+        ..Default::default()
+    };
+    let abstracts = |corpus: &SyntheticCorpus| -> Vec<String> {
+        corpus
+            .articles()
+            .into_iter()
+            .flatten()
+            .map(|a| a.paper_abstract)
+            .collect()
+    };
+    assert_eq!(abstracts(&corpus), abstracts(&corpus.clone()));
+    let reseeded = SyntheticCorpus {
+        seed: 2,
+        ..corpus.clone()
+    };
+    assert_ne!(abstracts(&corpus), abstracts(&reseeded));
+
+    let dir = fixture_dir("synthetic");
+    let files = corpus.write(&dir).unwrap();
+    assert_eq!(files.len(), 10);
+    let (_, article_database) = run_pipeline(&dir, pipeline_config(&dir, files));
+    assert_eq!(article_database.as_array().unwrap().len(), 20);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn in_memory_run_matches_the_written_databases_without_writing() {
+    let dir = fixture_dir("in_memory");
+    let config = pipeline_config(&dir, write_corpus(&dir));
+    let (rating_output, publications) = Analyzer::with_config(config.clone())
+        .run_in_memory()
+        .unwrap();
+    assert!(!dir.join("rating_database.json").exists());
+    assert!(!dir.join("article_database.json").exists());
+
+    let (rating_database, article_database) = run_pipeline(&dir, config);
+    let close = |a: &[f32], b: &[f32], tolerance: f32| {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() <= tolerance)
+    };
+    let written_ratings = rating_database["rating_output"].as_array().unwrap();
+    assert_eq!(rating_output.rating_output.len(), written_ratings.len());
+    for (rating, written) in rating_output.rating_output.iter().zip(written_ratings) {
+        assert_eq!(rating.keyword, written["keyword"].as_str().unwrap());
+        assert!(close(
+            &rating.rating,
+            &parse_rating(&written["rating"]),
+            1e-6
+        ));
+    }
+    let written_publications = article_database.as_array().unwrap();
+    assert_eq!(publications.len(), written_publications.len());
+    for (publication, written) in publications.iter().zip(written_publications) {
+        assert_eq!(publication.i, written["i"].as_str().unwrap());
+        // Written publication ratings are rounded to three decimals.
+        assert!(close(&publication.r, &parse_rating(&written["r"]), 5e-4));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cancelled_run_stops_before_writing_the_databases() {
+    let dir = fixture_dir("cancelled");
+    let config = pipeline_config(&dir, write_corpus(&dir));
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.set_cancellation_flag(flag.clone());
+    let canceller = flag.clone();
+    analyzer.set_progress_callback(move |_, _, _| canceller.store(true, Ordering::Relaxed));
+
+    assert_eq!(analyzer.run(), Err(AnalyzerError::Cancelled));
+    assert!(!dir.join("rating_database.json").exists());
+    assert!(!dir.join("article_database.json").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compressed_databases_and_shards_read_back() {
+    let dir = fixture_dir("compressed");
+    let corpus = write_corpus(&dir);
+    let plain = pipeline_config(&dir, corpus.clone());
+    Analyzer::with_config(plain.clone()).run().unwrap();
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+    let mut shard = pipeline_config(&dir, corpus.clone());
+    shard.shard_output = Some(path("shard.json.gz"));
+    Analyzer::with_config(shard).run().unwrap();
+    let mut compressed = pipeline_config(&dir, corpus);
+    compressed.shard_inputs = vec![path("shard.json.gz")];
+    compressed.rating_output_path = path("rating_database.json.gz");
+    compressed.article_output_path = path("article_database.json.gz");
+    Analyzer::with_config(compressed.clone()).run().unwrap();
+
+    let ratings = |path: &str| {
+        let model = RatingModel::load(path).unwrap();
+        let mut ratings: Vec<(String, Vec<f32>)> = model
+            .keywords()
+            .map(|k| (k.to_string(), model.rating(k).unwrap().to_vec()))
+            .collect();
+        ratings.sort_by(|a, b| a.0.cmp(&b.0));
+        ratings
+    };
+    assert_eq!(
+        ratings(&compressed.rating_output_path),
+        ratings(&plain.rating_output_path)
+    );
+    assert!(ValidationReport::of_file(&compressed.rating_output_path)
+        .unwrap()
+        .is_valid());
+    let ids = |path: &str| -> Vec<String> {
+        ArticleDatabase::load(path)
+            .unwrap()
+            .publications
+            .into_iter()
+            .map(|p| p.i)
+            .collect()
+    };
+    assert_eq!(
+        ids(&compressed.article_output_path),
+        ids(&plain.article_output_path)
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unreadable_inputs_fail_the_run() {
+    let dir = fixture_dir("unreadable_inputs");
+    let corpus = write_corpus(&dir);
+    let broken = dir.join("broken.json").to_string_lossy().to_string();
+    fs::write(&broken, "[{\"pmc\": ").unwrap();
+    let missing = dir.join("missing.json").to_string_lossy().to_string();
+    let fails_on = |config: AnalyzerConfig, file: &str| {
+        let err = Analyzer::with_config(config.clone()).run().unwrap_err();
+        match &err {
+            AnalyzerError::Input(InputError::Parse { file: f, .. })
+            | AnalyzerError::Input(InputError::Io { file: f, .. }) => assert_eq!(f, file),
+            _ => panic!("unexpected error: {}", err),
+        }
+    };
+
+    let mut training = pipeline_config(&dir, vec![corpus[0].clone(), broken.clone()]);
+    fails_on(training.clone(), &broken);
+    assert!(matches!(
+        Analyzer::with_config(training.clone()).run_in_memory(),
+        Err(AnalyzerError::Input(InputError::Parse { .. }))
+    ));
+    training.skip_malformed_articles = true;
+    fails_on(training, &broken);
+
+    let mut rating = pipeline_config(&dir, corpus.clone());
+    rating.rating_input_files = vec![broken.clone()];
+    fails_on(rating.clone(), &broken);
+    rating.stream_article_output = true;
+    fails_on(rating, &broken);
+
+    let mut ensemble = pipeline_config(&dir, corpus.clone());
+    ensemble.ensemble_models = vec![missing.clone()];
+    fails_on(ensemble, &missing);
+
+    let mut shards = pipeline_config(&dir, corpus);
+    shards.shard_inputs = vec![missing.clone()];
+    fails_on(shards, &missing);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cutoffs_that_purge_every_keyword_fail_the_run() {
+    let dir = fixture_dir("empty_vocabulary");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.lower_cutoff = 1.0;
+    let mut analyzer = Analyzer::with_config(config);
+
+    let err = analyzer.run().unwrap_err();
+    assert_eq!(
+        err,
+        AnalyzerError::EmptyVocabulary {
+            n_candidates: 16,
+            lower_bound: 2.0,
+            upper_bound: 20.0,
+            min_count: 1,
+            max_count: 2,
+        }
+    );
+    assert!(err.to_string().contains("lower `lower_cutoff`"));
+    assert!(!dir.join("rating_database.json").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn jsonl_article_output_writes_one_publication_per_line() {
+    let dir = fixture_dir("jsonl_output");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    let (_, article_database) = run_pipeline(&dir, config.clone());
+    let jsonl_path = dir.join("article_database.jsonl");
+    config.article_output_path = jsonl_path.to_string_lossy().to_string();
+    Analyzer::with_config(config.clone()).run().unwrap();
+
+    let contents = fs::read_to_string(&jsonl_path).unwrap();
+    let lines: Vec<Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines, *article_database.as_array().unwrap());
+    let loaded = ArticleDatabase::load(&config.article_output_path).unwrap();
+    assert_eq!(loaded.publications.len(), lines.len());
+    assert_eq!(loaded.format_version, FORMAT_VERSION);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn intermediate_outputs_are_written_on_request() {
+    let dir = fixture_dir("intermediate");
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.vocabulary_output_path = Some(path("vocabulary.txt"));
+    config.matrix_stats_output_path = Some(path("matrix_stats.json"));
+    config.seed_rating_output_path = Some(path("seed_ratings.json"));
+    let (rating_database, _) = run_pipeline(&dir, config);
+
+    let vocabulary = fs::read_to_string(dir.join("vocabulary.txt")).unwrap();
+    assert_eq!(
+        vocabulary.lines().collect::<Vec<_>>(),
+        keywords(&rating_database)
+    );
+    let stats: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("matrix_stats.json")).unwrap()).unwrap();
+    assert_eq!(stats["n_keywords"], keywords(&rating_database).len());
+    assert_eq!(stats["n_documents"], 4);
+    let seed_ratings: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("seed_ratings.json")).unwrap()).unwrap();
+    assert_eq!(keywords(&seed_ratings), keywords(&rating_database));
+    let n_rated = |database: &Value| {
+        database["rating_output"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|r| parse_rating(&r["rating"]).iter().any(|v| *v > 0.0))
+            .count()
+    };
+    assert!(n_rated(&seed_ratings) < n_rated(&rating_database));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn vocabulary_cap_keeps_the_most_frequent_keywords() {
+    let dir = fixture_dir("max_vocabulary");
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.max_vocabulary_size = Some(3);
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.run().unwrap();
+    let rating_database: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("rating_database.json")).unwrap())
+            .unwrap();
+
+    assert_eq!(
+        keywords(&rating_database),
+        vec!["angiogenesis", "apoptotic", "telomerase"]
+    );
+    assert!(analyzer
+        .summary()
+        .vocabulary_sizes
+        .contains(&("after the vocabulary cap".to_string(), 3)));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn only_partly_non_finite_publication_ratings_count() {
+    let publication = |r: Vec<f32>| RatedPublication {
+        r,
+        i: "PMC1".to_string(),
+        c: None,
+    };
+    let n_hallmarks = DEFAULT_HALLMARKS.len();
+    assert!(!publication(vec![f32::NAN; n_hallmarks]).has_non_finite_rating());
+    assert!(!publication(vec![0.1; n_hallmarks]).has_non_finite_rating());
+    let mut infinite = vec![0.0; n_hallmarks];
+    infinite[2] = f32::INFINITY;
+    assert!(publication(infinite).has_non_finite_rating());
+    let mut partly_nan = vec![0.0; n_hallmarks];
+    partly_nan[0] = f32::NAN;
+    assert!(publication(partly_nan).has_non_finite_rating());
+}
+
+#[test]
+fn adapted_ensemble_rates_with_the_hallmarks_of_its_models() {
+    let dir = fixture_dir("adapted_ensemble");
+    let model = dir.join("two_hallmarks.json").to_string_lossy().to_string();
+    fs::write(
+        &model,
+        json!({
+            "format_version": FORMAT_VERSION,
+            "hallmarks": [
+                {"title": "Growth", "description": ""},
+                {"title": "Death", "description": ""},
+            ],
+            "rating_output": [
+                {"keyword": "angiogenesis", "rating": [1.0, 0.0]},
+                {"keyword": "telomerase", "rating": [0.0, 1.0]},
+            ],
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let mut config = pipeline_config(&dir, write_corpus(&dir));
+    config.ensemble_models = vec![model];
+    config.hallmark_mismatch = HallmarkMismatch::Adapt;
+    config.publication_confidence = true;
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.run().unwrap();
+
+    let database =
+        ArticleDatabase::load(&dir.join("article_database.json").to_string_lossy()).unwrap();
+    assert_eq!(database.hallmarks, ["Growth", "Death"]);
+    let articles = &database.publications;
+    let ratings: Vec<(&str, Vec<f32>)> = articles
+        .iter()
+        .map(|p| (p.i.as_str(), p.r.clone()))
+        .collect();
+    assert_eq!(
+        ratings,
+        vec![("PMC1", vec![1.0, 0.0]), ("PMC2", vec![0.0, 1.0])]
+    );
+    let distribution = &analyzer.summary().corpus_distribution;
+    assert_eq!(distribution.hallmarks, ["Growth", "Death"]);
+    assert_eq!(distribution.dominant_counts, [1, 1]);
+    assert_eq!(articles[0].labels_above(0.5), [0]);
+    let markdown = PublicationReport::new(articles, &database.hallmarks)
+        .unwrap()
+        .render(ReportFormat::Markdown);
+    assert!(markdown.contains("## Growth (1)"));
+    assert!(markdown.contains("## Death (1)"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn one_model_ensemble_rates_like_the_trained_model() {
+    let dir = fixture_dir("one_model_ensemble");
+    let files = write_files(
+        &dir,
+        &[vec![
+            article(
+                "PMC1",
+                "Angiogenesis requires vascular endothelial growth and angiogenesis.",
+            ),
+            article(
+                "PMC2",
+                "Telomerase maintains telomeres during angiogenesis.",
+            ),
+            article("PMC3", "Apoptotic caspase cascades release telomerase."),
+            article("PMC4", "Telomerase maintains telomerase."),
+        ]],
+    );
+    let mut config = pipeline_config(&dir, files);
+    config.tf_weighting = TfWeighting::Log;
+    config.length_normalization = true;
+    config.min_relevant_keywords = 3;
+    let (rating_database, trained) = run_pipeline(&dir, config.clone());
+    let idf_table: HashMap<String, f32> = keywords(&rating_database)
+        .iter()
+        .enumerate()
+        .map(|(i, keyword)| (keyword.to_string(), 1.0 + (i % 3) as f32))
+        .collect();
+    config.idf_table = Some(idf_table);
+    config.idf_weighting = true;
+    let (_, trained_with_idf) = run_pipeline(&dir, config.clone());
+
+    config.ensemble_models = vec![config.rating_output_path.clone()];
+    config.article_output_path = dir
+        .join("ensemble_database.json")
+        .to_string_lossy()
+        .to_string();
+    Analyzer::with_config(config.clone()).run().unwrap();
+    let rated = ArticleDatabase::load(&config.article_output_path)
+        .unwrap()
+        .publications;
+    let trained_with_idf = trained_with_idf.as_array().unwrap();
+    assert_eq!(trained_with_idf.len(), 3, "{:?}", trained_with_idf);
+    assert_eq!(rated.len(), trained_with_idf.len());
+    for (publication, expected) in rated.iter().zip(trained_with_idf.iter()) {
+        assert_eq!(publication.i, expected["i"].as_str().unwrap());
+        for (r, e) in publication.r.iter().zip(parse_rating(&expected["r"])) {
+            assert!(
+                (r - e).abs() < 2e-3,
+                "{}: {:?} {:?}",
+                publication.i,
+                publication.r,
+                expected["r"]
+            );
+        }
+    }
+    assert_ne!(trained, Value::Array(trained_with_idf.clone()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn target_vocabulary_keeps_exactly_the_chosen_bound() {
+    let dir = fixture_dir("target_vocabulary");
+    let mut files = vec![vec![]; 11];
+    for k in 0..14 {
+        let mut text = String::from("Apoptosis");
+        if k < 13 {
+            text.push_str(" caspase");
+        }
+        if k < 5 {
+            text.push_str(" telomerase");
+        }
+        files[k % 11].push(article(&format!("PMC{}", k), &text));
+    }
+    let mut config = pipeline_config(&dir, write_files(&dir, &files));
+    // 13 abstracts over 11 files is no f32 that gives back 13 when multiplied by 11.
+    config.target_vocabulary_size = Some(1);
+    let (rating_database, _) = run_pipeline(&dir, config);
+
+    assert_eq!(keywords(&rating_database), vec!["apoptosis"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,77 @@
+use hcse_analyzer::analyzer::RatedPublication;
+use hcse_analyzer::publication_report::{PublicationReport, ReportFormat};
+use hcse_analyzer::{default_hallmark_titles, DEFAULT_HALLMARKS};
+
+fn publication(id: &str, dominant: usize, share: f32) -> RatedPublication {
+    let n = DEFAULT_HALLMARKS.len();
+    let mut r = vec![(1.0 - share) / (n - 1) as f32; n];
+    r[dominant] = share;
+    RatedPublication {
+        i: id.to_string(),
+        r,
+        c: None,
+    }
+}
+
+#[test]
+fn report_groups_by_dominant_hallmark_and_sorts_by_confidence() {
+    let publications = vec![
+        publication("PMC1", 4, 0.5),
+        publication("PMC2", 1, 0.9),
+        publication("PMC3", 4, 0.95),
+        publication("<b>", 1, 0.3),
+    ];
+    let hallmarks = default_hallmark_titles();
+    let report = PublicationReport::new(&publications, &hallmarks).unwrap();
+    let sections: Vec<(usize, Vec<&str>)> = report
+        .sections
+        .iter()
+        .map(|(hallmark, entries)| (*hallmark, entries.iter().map(|e| e.0.i.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        sections,
+        vec![(1, vec!["PMC2", "<b>"]), (4, vec!["PMC3", "PMC1"])]
+    );
+
+    let markdown = report.render(ReportFormat::Markdown);
+    let apoptosis = markdown
+        .find(&format!("## {} (2)", DEFAULT_HALLMARKS[1].title))
+        .unwrap();
+    let replication = markdown
+        .find(&format!("## {} (2)", DEFAULT_HALLMARKS[4].title))
+        .unwrap();
+    assert!(apoptosis < replication);
+    assert!(markdown.contains("| PMC3 | 0.950 |"));
+
+    let html = report.render(ReportFormat::Html);
+    assert!(html.contains("<td>&lt;b&gt;</td>"));
+    assert!(!html.contains("<td><b></td>"));
+}
+
+#[test]
+fn report_sections_follow_the_hallmarks_of_the_database() {
+    let hallmarks: Vec<String> = (0..12).map(|h| format!("Hallmark {}", h)).collect();
+    let mut r = vec![0.0; hallmarks.len()];
+    r[11] = 1.0;
+    let publications = vec![RatedPublication {
+        i: "PMC1".to_string(),
+        r,
+        c: None,
+    }];
+    let report = PublicationReport::new(&publications, &hallmarks).unwrap();
+    assert_eq!(report.sections.len(), 1);
+    assert_eq!(report.sections[0].0, 11);
+    assert!(report
+        .render(ReportFormat::Markdown)
+        .contains("## Hallmark 11 (1)"));
+
+    let defaults = default_hallmark_titles();
+    let err = PublicationReport::new(&publications, &defaults).unwrap_err();
+    assert_eq!(
+        err,
+        format!(
+            "publication 'PMC1' has 12 hallmark ratings instead of {}",
+            DEFAULT_HALLMARKS.len()
+        )
+    );
+}
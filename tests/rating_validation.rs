@@ -0,0 +1,55 @@
+use hcse_analyzer::rating_validation::ValidationReport;
+use hcse_analyzer::DEFAULT_HALLMARKS;
+use serde_json::{json, Value};
+
+fn database(rating_output: Value) -> Value {
+    json!({
+        "format_version": 1,
+        "hallmarks": DEFAULT_HALLMARKS,
+        "rating_output": rating_output,
+    })
+}
+
+#[test]
+fn valid_database_has_no_problems() {
+    let n = DEFAULT_HALLMARKS.len();
+    let mut rated = vec![0.0; n];
+    rated[3] = 1.0;
+    let report = ValidationReport::of_database(&database(json!([
+        {"keyword": "apoptosis", "rating": rated},
+        {"keyword": "unrated", "rating": vec![0.0; n]},
+    ])));
+    assert!(report.is_valid(), "{:?}", report.problems);
+    assert_eq!(report.n_keywords, 2);
+}
+
+#[test]
+fn every_kind_of_problem_is_reported() {
+    let n = DEFAULT_HALLMARKS.len();
+    let mut unnormalized = vec![0.0; n];
+    unnormalized[0] = 0.5;
+    let mut with_nan = vec![json!(0.0); n];
+    with_nan[1] = Value::Null;
+    let mut db = database(json!([
+        {"keyword": "short", "rating": [1.0]},
+        {"keyword": "unnormalized", "rating": unnormalized},
+        {"keyword": "nan", "rating": with_nan},
+    ]));
+    db["hallmarks"][2]["title"] = json!("Something else");
+    db["format_version"] = json!(99);
+    let report = ValidationReport::of_database(&db);
+
+    assert_eq!(
+        report.problems,
+        vec![
+            "format version 99 is newer than the supported version 1".to_string(),
+            format!(
+                "hallmark 2 is 'Something else' instead of '{}'",
+                DEFAULT_HALLMARKS[2].title
+            ),
+            format!("keyword 'short' has 1 hallmark ratings instead of {}", n),
+            "ratings of keyword 'unnormalized' sum to 0.5 instead of 1".to_string(),
+            "keyword 'nan' has a NaN or missing rating".to_string(),
+        ]
+    );
+}
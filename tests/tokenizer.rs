@@ -0,0 +1,188 @@
+use hcse_analyzer::config::{HyphenMode, TokenizerConfig, GREEK_LETTERS, STATISTICAL_PATTERNS};
+use hcse_analyzer::tokenizer::{tokens_of, Tokenizer};
+use std::collections::HashMap;
+
+fn tokenize(text: &str, hyphen_mode: HyphenMode) -> Vec<String> {
+    let config = TokenizerConfig {
+        hyphen_mode,
+        ..Default::default()
+    };
+    Tokenizer::new(&config).unwrap().unique_tokens(text)
+}
+
+#[test]
+fn hyphen_mode_keep_leaves_compounds_intact() {
+    let words = tokenize("Treating non-small-cell carcinoma.", HyphenMode::Keep);
+    assert_eq!(words, vec!["carcinoma", "non-small-cell", "treating"]);
+}
+
+#[test]
+fn hyphen_mode_split_replaces_compounds_by_parts() {
+    let words = tokenize(
+        "Anti-apoptotic proteins in non-small-cell carcinoma.",
+        HyphenMode::Split,
+    );
+    assert_eq!(words, vec!["apoptotic", "carcinoma", "proteins", "small"]);
+}
+
+#[test]
+fn hyphen_mode_both_emits_compounds_and_parts() {
+    let words = tokenize("Anti-apoptotic proteins.", HyphenMode::Both);
+    assert_eq!(words, vec!["anti-apoptotic", "apoptotic", "proteins"]);
+}
+
+#[test]
+fn leading_and_trailing_hyphens_are_stripped_in_every_mode() {
+    for mode in [HyphenMode::Keep, HyphenMode::Split, HyphenMode::Both] {
+        assert_eq!(tokenize("--tumor-", mode), vec!["tumor"]);
+    }
+}
+
+#[test]
+fn synonyms_collapse_aliases_into_the_canonical_token() {
+    let config = TokenizerConfig {
+        synonyms: HashMap::from([
+            ("neoplasm".to_string(), "tumor".to_string()),
+            ("tumour".to_string(), "tumor".to_string()),
+        ]),
+        ..Default::default()
+    };
+    let tokenizer = Tokenizer::new(&config).unwrap();
+    assert_eq!(
+        tokenizer.tokenize("Neoplasm and tumour growth."),
+        vec!["growth", "tumor", "tumor"]
+    );
+    assert_eq!(tokenizer.canonical("tumour"), "tumor");
+    assert_eq!(tokenizer.canonical("growth"), "growth");
+}
+
+#[test]
+fn lemmas_replace_surface_forms_before_synonyms() {
+    let config = TokenizerConfig {
+        lemmas: HashMap::from([
+            ("tumours".to_string(), "tumour".to_string()),
+            ("proteins".to_string(), "protein".to_string()),
+        ]),
+        synonyms: HashMap::from([("tumour".to_string(), "tumor".to_string())]),
+        ..Default::default()
+    };
+    let tokenizer = Tokenizer::new(&config).unwrap();
+    assert_eq!(
+        tokenizer.tokenize("Tumours express proteins and the protein."),
+        vec!["express", "protein", "protein", "tumor"]
+    );
+    assert_eq!(tokenizer.canonical("tumours"), "tumor");
+    assert_eq!(tokenizer.canonical("growth"), "growth");
+}
+
+#[test]
+fn greek_letters_are_transliterated_before_punctuation_is_removed() {
+    let config = TokenizerConfig {
+        transliterations: GREEK_LETTERS
+            .iter()
+            .map(|(letter, latin)| (*letter, latin.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    let words = Tokenizer::new(&config)
+        .unwrap()
+        .unique_tokens("Β-catenin activates TGF-β.");
+    assert_eq!(words, vec!["activates", "beta-catenin", "tgf-beta"]);
+}
+
+#[test]
+fn unit_words_replace_percent_signs_and_units() {
+    let text = "Survival rose by 50% at 37°C.";
+    let stripped = Tokenizer::new(&TokenizerConfig::default())
+        .unwrap()
+        .unique_tokens(text);
+    assert_eq!(stripped, vec!["37°c", "survival"]);
+
+    let config = TokenizerConfig {
+        unit_words: true,
+        ..Default::default()
+    };
+    let words = Tokenizer::new(&config).unwrap().unique_tokens(text);
+    assert_eq!(words, vec!["celsius", "percent", "survival"]);
+}
+
+#[test]
+fn unit_words_leave_words_starting_with_a_unit_alone() {
+    let config = TokenizerConfig {
+        unit_words: true,
+        ..Default::default()
+    };
+    let words = Tokenizer::new(&config)
+        .unwrap()
+        .unique_tokens("Cells took up 5 µmol glucose through 10µm pores.");
+    assert_eq!(
+        words,
+        vec!["cells", "glucose", "micrometer", "pores", "through", "µmol"]
+    );
+}
+
+#[test]
+fn unit_words_apply_before_greek_letters_are_transliterated() {
+    let config = TokenizerConfig {
+        unit_words: true,
+        transliterations: GREEK_LETTERS
+            .iter()
+            .map(|(letter, latin)| (*letter, latin.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    let words = Tokenizer::new(&config)
+        .unwrap()
+        .unique_tokens("Pores of 5 μm bind μ-opioid ligands.");
+    assert_eq!(words, vec!["ligands", "micrometer", "mu-opioid", "pores"]);
+}
+
+#[test]
+fn protected_matches_survive_however_short_they_are() {
+    let text = "Survival rose by 50% with 10 mg cisplatin (p<0.05, p = 0.00012).";
+    let unprotected = Tokenizer::new(&TokenizerConfig::default())
+        .unwrap()
+        .unique_tokens(text);
+    assert_eq!(unprotected, vec!["00012", "cisplatin", "survival"]);
+
+    let mut config = TokenizerConfig {
+        protected_patterns: STATISTICAL_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        ..Default::default()
+    };
+    let words = Tokenizer::new(&config).unwrap().unique_tokens(text);
+    assert_eq!(
+        words,
+        vec![
+            "10mg",
+            "50%",
+            "cisplatin",
+            "p<0.05",
+            "p=0.00012",
+            "survival"
+        ]
+    );
+
+    config.drop_protected = true;
+    let words = Tokenizer::new(&config).unwrap().unique_tokens(text);
+    assert_eq!(words, vec!["cisplatin", "survival"]);
+}
+
+#[test]
+fn token_stream_keeps_repetitions_unless_deduplicated() {
+    let config = TokenizerConfig::default();
+    let text = "Tumor cells, tumor growth (tumor).";
+    assert_eq!(
+        tokens_of(text, false, &config).unwrap(),
+        vec!["cells", "growth", "tumor", "tumor", "tumor"]
+    );
+    assert_eq!(
+        tokens_of(text, true, &config).unwrap(),
+        vec!["cells", "growth", "tumor"]
+    );
+
+    let invalid = TokenizerConfig {
+        protected_patterns: vec!["(".to_string()],
+        ..Default::default()
+    };
+    assert!(tokens_of(text, false, &invalid).is_err());
+}